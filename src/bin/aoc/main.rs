@@ -0,0 +1,493 @@
+//!
+
+use aoc2023::bench::BenchConfig;
+use aoc2023::input::InputSource;
+use aoc2023::runner::{emit_dot, render, run_solver, RunError, RunOptions};
+use aoc2023::runtime_log::{self, Baseline, RuntimeLog};
+use aoc2023::scaffold;
+use aoc2023::solver::Part;
+use std::path::Path;
+use std::process::ExitCode;
+
+macro_rules! register_days {
+	($($day:ident: $number:literal => $path:literal,)*) => {
+		$(
+			#[allow(dead_code)]
+			#[path = $path]
+			mod $day;
+		)*
+
+		const DAYS: &[Day] = &[
+			$(
+				Day {
+					number: $number,
+					name: stringify!($day),
+					run: |input, part, options| {
+						run_solver::<$day::Solution>(stringify!($day), input, part, options)
+							.map(|_| ())
+					},
+					emit_dot: emit_dot::<$day::Solution>,
+					render: render::<$day::Solution>,
+				},
+			)*
+		];
+	};
+}
+
+register_days! {
+	day01: 1 => "../day01/main.rs",
+	day02: 2 => "../day02/main.rs",
+	day03: 3 => "../day03/main.rs",
+	day04: 4 => "../day04/main.rs",
+	day05: 5 => "../day05/main.rs",
+	day06: 6 => "../day06/main.rs",
+	day07: 7 => "../day07/main.rs",
+	day08: 8 => "../day08/main.rs",
+	day09: 9 => "../day09/main.rs",
+	day10: 10 => "../day10/main.rs",
+	day11: 11 => "../day11/main.rs",
+	day12: 12 => "../day12/main.rs",
+	day13: 13 => "../day13/main.rs",
+	day14: 14 => "../day14/main.rs",
+	day15: 15 => "../day15/main.rs",
+	day16: 16 => "../day16/main.rs",
+	day17: 17 => "../day17/main.rs",
+	day18: 18 => "../day18/main.rs",
+	day19: 19 => "../day19/main.rs",
+	day20: 20 => "../day20/main.rs",
+	day21: 21 => "../day21/main.rs",
+	day22: 22 => "../day22/main.rs",
+	day23: 23 => "../day23/main.rs",
+}
+
+struct Day
+{
+	number: u8,
+	name: &'static str,
+	run: fn(&str, Part, &RunOptions) -> Result<(), RunError>,
+	emit_dot: fn(&str, &Path) -> Result<(), RunError>,
+	render: fn(&str, Part) -> Result<String, RunError>,
+}
+
+const USAGE: &str = "usage: aoc run <DAYS> [--part one|two] [--input PATH] \
+                     [--bench [--runs N]] [--record] [--emit-dot OUT.dot] \
+                     [--render]
+       aoc new <DAY> [EXAMPLE.txt...]
+       aoc perf-diff [--baseline previous|REV] [--threshold PERCENT] [--log \
+                     PATH]
+
+DAYS is a day number such as 17, a range such as 3..9 or 3..=9, or all.
+PATH defaults to src/bin/dayNN/input.txt; use - to read from stdin.
+--bench runs each part many times and reports min, median, mean and p95;
+setting AOC_BENCH=1 (and optionally AOC_BENCH_RUNS=N) does the same.
+Answers on a day's own input are checked against its answers.toml; --record
+(or AOC_RECORD=1) stores them as the new expected answers instead.
+--emit-dot writes the parsed input of a single day as a Graphviz graph, for
+days 8, 19, 20 and 23; render it with `dot -Tsvg OUT.dot > out.svg`.
+--render prints how a single day solves each part before running it, for
+day 17 (the crucible's route, drawn with arrows).
+Days 16 and 23 spread part two over as many threads as the machine runs at
+once; AOC_THREADS=N sets how many, and AOC_THREADS=1 solves day 23 without
+splitting the search.
+
+new creates src/bin/dayNN from src/template/main.rs with an empty input.txt
+and provided.txt, plus an empty file and a pair of tests for every EXAMPLE.
+
+perf-diff compares the latest logged run of each day and part with the run
+before it, or with the latest run at git revision REV, and fails if any got
+slower by more than PERCENT (default 10).";
+
+pub fn main() -> ExitCode
+{
+	let args: Vec<String> = std::env::args().skip(1).collect();
+	let result = match args.first().map(String::as_str)
+	{
+		Some("run") => run(&args[1..]),
+		Some("new") => new_day(&args[1..]),
+		Some("perf-diff") => perf_diff(&args[1..]),
+		Some("help" | "--help" | "-h") =>
+		{
+			println!("{USAGE}");
+			Ok(ExitCode::SUCCESS)
+		}
+		_ => Err(USAGE.to_string()),
+	};
+	match result
+	{
+		Ok(code) => code,
+		Err(message) =>
+		{
+			eprintln!("{message}");
+			ExitCode::FAILURE
+		}
+	}
+}
+
+fn run(args: &[String]) -> Result<ExitCode, String>
+{
+	let mut selection = None;
+	let mut parts = Part::BOTH.to_vec();
+	let mut input_arg = None;
+	let mut bench = None;
+	let mut record = false;
+	let mut dot_path = None;
+	let mut is_rendering = false;
+	let mut args = args.iter();
+	while let Some(arg) = args.next()
+	{
+		match arg.as_str()
+		{
+			"--part" =>
+			{
+				let value = args.next().ok_or("--part needs a value")?;
+				parts = vec![parse_part(value)?];
+			}
+			"--input" =>
+			{
+				let value = args.next().ok_or("--input needs a value")?;
+				input_arg = Some(value.as_str());
+			}
+			"--bench" => bench = bench.or(Some(BenchConfig::default())),
+			"--record" => record = true,
+			"--emit-dot" =>
+			{
+				let value = args.next().ok_or("--emit-dot needs a value")?;
+				dot_path = Some(Path::new(value));
+			}
+			"--render" => is_rendering = true,
+			"--runs" =>
+			{
+				let value = args.next().ok_or("--runs needs a value")?;
+				let num_runs =
+					value.parse().ok().filter(|&x| x > 0).ok_or_else(|| {
+						format!("invalid number of runs '{value}'")
+					})?;
+				bench = Some(BenchConfig::with_runs(num_runs));
+			}
+			_ if selection.is_none() => selection = Some(select_days(arg)?),
+			_ => return Err(format!("unexpected argument '{arg}'\n{USAGE}")),
+		}
+	}
+	let days = selection.ok_or(USAGE)?;
+	if input_arg.is_some() && days.len() > 1
+	{
+		return Err("--input can only be used with a single day".to_string());
+	}
+	if dot_path.is_some() && days.len() > 1
+	{
+		return Err("--emit-dot can only be used with a single day".to_string());
+	}
+	if is_rendering && days.len() > 1
+	{
+		return Err("--render can only be used with a single day".to_string());
+	}
+	if input_arg.is_some() && record
+	{
+		return Err("--record only works with the day's own input".to_string());
+	}
+
+	let mut is_success = true;
+	for day in days
+	{
+		let source = InputSource::from_arg(input_arg, day.name);
+		let input = match source.read()
+		{
+			Ok(input) => input,
+			Err(error) =>
+			{
+				eprintln!("{}: {error}", day.name);
+				is_success = false;
+				continue;
+			}
+		};
+		if let Some(path) = dot_path
+		{
+			if let Err(error) = (day.emit_dot)(&input, path)
+			{
+				eprintln!("{} {error}", day.name);
+				is_success = false;
+				continue;
+			}
+		}
+		if is_rendering
+		{
+			for &part in &parts
+			{
+				match (day.render)(&input, part)
+				{
+					Ok(picture) =>
+					{
+						print!("{} part {part}:\n{picture}", day.name)
+					}
+					Err(error) =>
+					{
+						eprintln!("{} part {part}: {error}", day.name);
+						is_success = false;
+					}
+				}
+			}
+		}
+		let mut options = RunOptions::from_env(day.name, &source);
+		if bench.is_some()
+		{
+			options.bench = bench;
+		}
+		options.record |= record;
+		for &part in &parts
+		{
+			match (day.run)(&input, part, &options)
+			{
+				Ok(()) => (),
+				Err(error @ RunError::Parse(_)) =>
+				{
+					eprintln!("{} {error}", day.name);
+					is_success = false;
+					break;
+				}
+				Err(error) =>
+				{
+					eprintln!("{} part {part}: {error}", day.name);
+					is_success = false;
+				}
+			}
+		}
+	}
+
+	if is_success
+	{
+		Ok(ExitCode::SUCCESS)
+	}
+	else
+	{
+		Ok(ExitCode::FAILURE)
+	}
+}
+
+fn new_day(args: &[String]) -> Result<ExitCode, String>
+{
+	let (day, examples) = args.split_first().ok_or(USAGE)?;
+	let number = day
+		.trim_start_matches("day")
+		.parse::<u8>()
+		.ok()
+		.filter(|x| (1..=25).contains(x))
+		.ok_or_else(|| format!("invalid day '{day}'"))?;
+	let name = format!("day{number:02}");
+	let examples: Vec<&str> = examples.iter().map(String::as_str).collect();
+
+	let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+	let dir = root.join("src").join("bin").join(&name);
+	if dir.exists()
+	{
+		return Err(format!("{} already exists", dir.display()));
+	}
+	let template = include_str!("../../template/main.rs");
+	let source = scaffold::render_day(template, &examples)?;
+	let runner_path = root.join("src").join("bin").join("aoc").join("main.rs");
+	let runner_source = std::fs::read_to_string(&runner_path)
+		.map_err(|error| format!("{}: {error}", runner_path.display()))?;
+	let runner_source = scaffold::register_day(&runner_source, number)?;
+
+	let mut files = vec![
+		(dir.join("main.rs"), source),
+		(dir.join("input.txt"), String::new()),
+		(dir.join("provided.txt"), String::new()),
+	];
+	for example in examples
+	{
+		let (file_name, _) = scaffold::example_names(example)?;
+		files.push((dir.join(file_name), String::new()));
+	}
+	let write = |path: &Path, contents: &str| {
+		std::fs::write(path, contents)
+			.map_err(|error| format!("{}: {error}", path.display()))
+	};
+	std::fs::create_dir_all(&dir)
+		.map_err(|error| format!("{}: {error}", dir.display()))?;
+	for (path, contents) in &files
+	{
+		write(path, contents)?;
+		println!("created {}", path.display());
+	}
+	write(&runner_path, &runner_source)?;
+	println!("registered {name} in {}", runner_path.display());
+	Ok(ExitCode::SUCCESS)
+}
+
+fn perf_diff(args: &[String]) -> Result<ExitCode, String>
+{
+	let mut baseline = Baseline::Previous;
+	let mut threshold_in_percent = 10.0;
+	let mut path = runtime_log::default_path();
+	let mut args = args.iter();
+	while let Some(arg) = args.next()
+	{
+		match arg.as_str()
+		{
+			"--baseline" =>
+			{
+				let value = args.next().ok_or("--baseline needs a value")?;
+				baseline = match value.as_str()
+				{
+					"previous" => Baseline::Previous,
+					rev => Baseline::Revision(rev.to_string()),
+				};
+			}
+			"--threshold" =>
+			{
+				let value = args.next().ok_or("--threshold needs a value")?;
+				threshold_in_percent = value
+					.trim_end_matches('%')
+					.parse()
+					.map_err(|_| format!("invalid threshold '{value}'"))?;
+			}
+			"--log" =>
+			{
+				let value = args.next().ok_or("--log needs a value")?;
+				path = value.into();
+			}
+			_ => return Err(format!("unexpected argument '{arg}'\n{USAGE}")),
+		}
+	}
+
+	let log = RuntimeLog::read(&path)?;
+	let comparisons = log.compare(&baseline);
+	if comparisons.is_empty()
+	{
+		return Err(format!("nothing to compare in {}", path.display()));
+	}
+
+	println!(
+		"{:<6} {:<4} {:<8} {:>12} {:>12} {:>9}",
+		"day", "part", "profile", "baseline ms", "latest ms", "change"
+	);
+	let mut num_regressions = 0;
+	for comparison in &comparisons
+	{
+		let is_regression = comparison.is_regression(threshold_in_percent);
+		if is_regression
+		{
+			num_regressions += 1;
+		}
+		println!(
+			"{:<6} {:<4} {:<8} {:>12.3} {:>12.3} {:>+8.1}%{}",
+			comparison.latest.day,
+			comparison.latest.part,
+			comparison.latest.profile,
+			comparison.baseline.runtime_in_ms,
+			comparison.latest.runtime_in_ms,
+			comparison.change_in_percent(),
+			if is_regression { "  REGRESSION" } else { "" }
+		);
+	}
+
+	if num_regressions > 0
+	{
+		eprintln!(
+			"{num_regressions} regression(s) above {threshold_in_percent}%"
+		);
+		Ok(ExitCode::FAILURE)
+	}
+	else
+	{
+		Ok(ExitCode::SUCCESS)
+	}
+}
+
+fn parse_part(value: &str) -> Result<Part, String>
+{
+	match value
+	{
+		"1" => Ok(Part::One),
+		"2" => Ok(Part::Two),
+		_ => value.parse().map_err(|_| {
+			format!("unknown part '{value}', expected one or two")
+		}),
+	}
+}
+
+fn select_days(arg: &str) -> Result<Vec<&'static Day>, String>
+{
+	if arg == "all"
+	{
+		return Ok(DAYS.iter().collect());
+	}
+
+	let parse_number = |x: &str| {
+		x.trim_start_matches("day")
+			.parse::<u8>()
+			.map_err(|_| format!("invalid day '{x}'"))
+	};
+	let range = if let Some((start, end)) = arg.split_once("..=")
+	{
+		parse_number(start)?..=parse_number(end)?
+	}
+	else if let Some((start, end)) = arg.split_once("..")
+	{
+		let end = parse_number(end)?;
+		if end == 0
+		{
+			return Err(format!("empty range '{arg}'"));
+		}
+		parse_number(start)?..=(end - 1)
+	}
+	else
+	{
+		let number = parse_number(arg)?;
+		let day = DAYS.iter().find(|day| day.number == number);
+		return match day
+		{
+			Some(day) => Ok(vec![day]),
+			None => Err(format!("day {number} is not implemented")),
+		};
+	};
+
+	let days: Vec<&'static Day> = DAYS
+		.iter()
+		.filter(|day| range.contains(&day.number))
+		.collect();
+	if days.is_empty()
+	{
+		return Err(format!("no implemented days in '{arg}'"));
+	}
+	Ok(days)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	fn numbers(arg: &str) -> Vec<u8>
+	{
+		select_days(arg)
+			.unwrap()
+			.iter()
+			.map(|day| day.number)
+			.collect()
+	}
+
+	#[test]
+	fn test_registry()
+	{
+		for (i, day) in DAYS.iter().enumerate()
+		{
+			assert_eq!(day.number as usize, i + 1);
+			assert_eq!(day.name, format!("day{:02}", day.number));
+		}
+	}
+
+	#[test]
+	fn test_select_days()
+	{
+		assert_eq!(numbers("17"), vec![17]);
+		assert_eq!(numbers("day05"), vec![5]);
+		assert_eq!(numbers("3..9"), vec![3, 4, 5, 6, 7, 8]);
+		assert_eq!(numbers("3..=9"), vec![3, 4, 5, 6, 7, 8, 9]);
+		assert_eq!(&numbers("20..=99")[0..3], &[20, 21, 22]);
+		assert_eq!(numbers("all").len(), DAYS.len());
+		assert!(select_days("99").is_err());
+		assert!(select_days("seventeen").is_err());
+	}
+}
@@ -1,31 +1,56 @@
 /**/
 
-use aoc2023::run;
-
-const INPUT: &str = include_str!("input.txt");
+use aoc2023::parse::{self, ParseError};
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> i32
-{
-	input
-		.lines()
-		.filter(|line| !line.is_empty())
-		.map(decode_line_using_only_digits)
-		.sum()
-}
+pub struct Solution;
 
-fn two(input: &str) -> i32
+impl Solver for Solution
 {
-	input
-		.lines()
-		.filter(|line| !line.is_empty())
-		.map(decode_line_using_digits_and_words)
-		.sum()
+	type Parsed<'a> = Vec<&'a str>;
+	type Answer = i32;
+
+	fn parse(input: &str) -> Result<Vec<&str>, ParseError>
+	{
+		parse::lines(input)
+			.map(|line| {
+				match line.find(|x: char| {
+					!x.is_ascii_digit() && !x.is_ascii_lowercase()
+				})
+				{
+					Some(i) => Err(ParseError::at(
+						input,
+						&line[i..],
+						"expected a digit or a lowercase letter",
+					)),
+					None => Ok(line),
+				}
+			})
+			.collect()
+	}
+
+	fn part_one(lines: &Vec<&str>) -> i32
+	{
+		lines
+			.iter()
+			.map(|line| decode_line_using_only_digits(line))
+			.sum()
+	}
+
+	fn part_two(lines: &Vec<&str>) -> i32
+	{
+		lines
+			.iter()
+			.map(|line| decode_line_using_digits_and_words(line))
+			.sum()
+	}
 }
 
 fn decode_line_using_only_digits(line: &str) -> i32
@@ -124,7 +149,7 @@ impl<const SPEED: i32> Automaton<SPEED>
 					word == &self.buffer[start..end]
 				})
 			}
-			_ => panic!("Unexpected char: {x}"),
+			_ => unreachable!("rejected by parse"),
 		}
 	}
 }
@@ -141,13 +166,13 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 142);
+		assert_eq!(Solution::one(PROVIDED), 142);
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED2), 281);
+		assert_eq!(Solution::two(PROVIDED2), 281);
 	}
 
 	#[test]
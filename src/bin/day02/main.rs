@@ -1,39 +1,49 @@
 /**/
 
-use aoc2023::run;
+use aoc2023::parse::{self, ParseError};
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 
 use parse_display::{Display, FromStr};
 use std::str::FromStr;
 
-const INPUT: &str = include_str!("input.txt");
-
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> i32
-{
-	let max = Rgb {
-		red: 12,
-		green: 13,
-		blue: 14,
-	};
-	input
-		.lines()
-		.filter(|line| !line.is_empty())
-		.filter_map(|line| game_number_if_matches_max(line, max))
-		.sum()
-}
+pub struct Solution;
 
-fn two(input: &str) -> i32
+impl Solver for Solution
 {
-	input
-		.lines()
-		.filter(|line| !line.is_empty())
-		.map(power_of_game)
-		.sum()
+	type Parsed<'a> = Vec<Game>;
+	type Answer = i32;
+
+	fn parse(input: &str) -> Result<Vec<Game>, ParseError>
+	{
+		parse::lines(input)
+			.map(|line| parse_game(input, line))
+			.collect()
+	}
+
+	fn part_one(games: &Vec<Game>) -> i32
+	{
+		let max = Rgb {
+			red: 12,
+			green: 13,
+			blue: 14,
+		};
+		games
+			.iter()
+			.filter_map(|game| game_number_if_matches_max(game, max))
+			.sum()
+	}
+
+	fn part_two(games: &Vec<Game>) -> i32
+	{
+		games.iter().map(power_of_game).sum()
+	}
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -93,21 +103,53 @@ struct Preamble
 	number: i32,
 }
 
-fn game_number_if_matches_max(line: &str, max: Rgb) -> Option<i32>
+#[derive(Debug)]
+pub struct Game
 {
-	let (preamble, body) = line.split_once(':').unwrap();
-	let preamble = Preamble::from_str(preamble).unwrap();
-	let number = preamble.number;
+	number: i32,
+	samples: Vec<Sample>,
+}
 
-	let is_possible = body
+fn parse_game(input: &str, line: &str) -> Result<Game, ParseError>
+{
+	let (preamble, body) = line
+		.split_once(':')
+		.ok_or_else(|| ParseError::after(input, line, "expected ':'"))?;
+	let preamble = Preamble::from_str(preamble).map_err(|_| {
+		ParseError::at(input, preamble, "expected 'Game <number>'")
+	})?;
+	let samples = body
 		.split(';')
-		.flat_map(|grab| grab.trim().split(','))
-		.map(|sample| Sample::from_str(sample.trim()).unwrap())
+		.flat_map(|grab| grab.split(','))
+		.map(|sample| {
+			let sample = sample.trim();
+			Sample::from_str(sample).map_err(|_| {
+				ParseError::at(
+					input,
+					sample,
+					format!(
+						"expected '<number> red|green|blue', found '{sample}'"
+					),
+				)
+			})
+		})
+		.collect::<Result<_, _>>()?;
+	Ok(Game {
+		number: preamble.number,
+		samples,
+	})
+}
+
+fn game_number_if_matches_max(game: &Game, max: Rgb) -> Option<i32>
+{
+	let is_possible = game
+		.samples
+		.iter()
 		.all(|sample| sample.is_allowed_by_max(max));
 
 	if is_possible
 	{
-		Some(number)
+		Some(game.number)
 	}
 	else
 	{
@@ -115,16 +157,10 @@ fn game_number_if_matches_max(line: &str, max: Rgb) -> Option<i32>
 	}
 }
 
-fn power_of_game(line: &str) -> i32
+fn power_of_game(game: &Game) -> i32
 {
-	let (_preamble, body) = line.split_once(':').unwrap();
-
 	let mut max = Rgb::default();
-	let samples = body
-		.split(';')
-		.flat_map(|grab| grab.trim().split(','))
-		.map(|sample| Sample::from_str(sample.trim()).unwrap());
-	for sample in samples
+	for sample in &game.samples
 	{
 		sample.raise_max(&mut max);
 	}
@@ -143,12 +179,12 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 8);
+		assert_eq!(Solution::one(PROVIDED), 8);
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 2286);
+		assert_eq!(Solution::two(PROVIDED), 2286);
 	}
 }
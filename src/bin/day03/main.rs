@@ -1,29 +1,81 @@
 /**/
 
+use aoc2023::parse::{self, ParseError};
 use aoc2023::ring_buffer::RingBuffer;
-use aoc2023::run;
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 use bitvec::array::BitArray;
 use itertools::Itertools;
 
-const INPUT: &str = include_str!("input.txt");
+const MAX_WIDTH: usize = 200;
 
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> i32
+pub struct Solution;
+
+impl Solver for Solution
 {
-	let lines = input.lines().filter(|line| !line.is_empty());
+	type Parsed<'a> = Vec<&'a str>;
+	type Answer = i32;
+
+	fn parse(input: &str) -> Result<Vec<&str>, ParseError>
+	{
+		let lines: Vec<&str> = parse::lines(input).collect();
+		let width = lines.first().map_or(0, |line| line.len());
+		for line in &lines
+		{
+			if let Some(i) = line.find(|x: char| !x.is_ascii_graphic())
+			{
+				return Err(ParseError::at(
+					input,
+					&line[i..],
+					"expected a digit, '.' or a symbol",
+				));
+			}
+			if line.len() != width
+			{
+				return Err(ParseError::at(
+					input,
+					line,
+					format!("expected {width} characters like the first line"),
+				));
+			}
+			if line.len() > MAX_WIDTH
+			{
+				return Err(ParseError::at(
+					input,
+					&line[MAX_WIDTH..],
+					format!("lines longer than {MAX_WIDTH} are not supported"),
+				));
+			}
+		}
+		Ok(lines)
+	}
+
+	fn part_one(lines: &Vec<&str>) -> i32
+	{
+		sum_part_numbers(lines)
+	}
 
+	fn part_two(lines: &Vec<&str>) -> i32
+	{
+		sum_gear_ratios(lines)
+	}
+}
+
+fn sum_part_numbers(lines: &[&str]) -> i32
+{
 	let mut resolved_sum = 0;
 	let mut unresolved: RingBuffer<[UnresolvedNumber; 20]> =
 		RingBuffer::default();
 	let mut previous_symbols: BitArray<[u64; 4]> = BitArray::default();
 	let mut current_symbols: BitArray<[u64; 4]> = BitArray::default();
 
-	for (line_number, line) in lines.enumerate()
+	for (line_number, line) in lines.iter().enumerate()
 	{
 		while let Some(head) = unresolved.head()
 		{
@@ -165,16 +217,15 @@ impl Grapheme
 	}
 }
 
-fn two(input: &str) -> i32
+fn sum_gear_ratios(lines: &[&str]) -> i32
 {
 	let mut gear_ratio_sum = 0;
 
-	let width = input.lines().next().unwrap().len();
-	let dots = [b'.'; 200];
+	let width = lines.first().map_or(0, |line| line.len());
+	let dots = [b'.'; MAX_WIDTH];
 	let empty = std::str::from_utf8(&dots[0..width]).unwrap();
-	let lines = input.lines().filter(|line| !line.is_empty());
 	let lines = std::iter::once(empty)
-		.chain(lines)
+		.chain(lines.iter().copied())
 		.chain(std::iter::once(empty));
 	for (prev, curr, next) in lines.tuple_windows()
 	{
@@ -350,70 +401,70 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 4361);
+		assert_eq!(Solution::one(PROVIDED), 4361);
 	}
 
 	#[test]
 	fn one_left()
 	{
-		assert_eq!(one("+123"), 123);
+		assert_eq!(Solution::one("+123"), 123);
 	}
 
 	#[test]
 	fn one_right()
 	{
-		assert_eq!(one("123*"), 123);
+		assert_eq!(Solution::one("123*"), 123);
 	}
 
 	#[test]
 	fn one_above()
 	{
-		assert_eq!(one("...+\n123."), 123);
-		assert_eq!(one("+...\n.456"), 456);
+		assert_eq!(Solution::one("...+\n123."), 123);
+		assert_eq!(Solution::one("+...\n.456"), 456);
 	}
 
 	#[test]
 	fn one_below()
 	{
-		assert_eq!(one(".123\n$..."), 123);
-		assert_eq!(one("456.\n...$"), 456);
-		assert_eq!(one("9.10\n.$.."), 19);
-		assert_eq!(one("1.2..4\n.$...."), 3);
+		assert_eq!(Solution::one(".123\n$..."), 123);
+		assert_eq!(Solution::one("456.\n...$"), 456);
+		assert_eq!(Solution::one("9.10\n.$.."), 19);
+		assert_eq!(Solution::one("1.2..4\n.$...."), 3);
 	}
 
 	#[test]
 	fn one_none()
 	{
-		assert_eq!(one("......\n123..."), 0);
-		assert_eq!(one(".....$\n456..."), 0);
-		assert_eq!(one(".$....\n......\n789..."), 0);
-		assert_eq!(one(".111\n"), 0);
+		assert_eq!(Solution::one("......\n123..."), 0);
+		assert_eq!(Solution::one(".....$\n456..."), 0);
+		assert_eq!(Solution::one(".$....\n......\n789..."), 0);
+		assert_eq!(Solution::one(".111\n"), 0);
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 467835);
+		assert_eq!(Solution::two(PROVIDED), 467835);
 	}
 
 	#[test]
 	fn two_singlet()
 	{
-		assert_eq!(two("1*.\n..."), 0);
+		assert_eq!(Solution::two("1*.\n..."), 0);
 	}
 
 	#[test]
 	fn two_gears()
 	{
-		assert_eq!(two("10*20"), 200);
-		assert_eq!(two("30.\n.*.\n.40"), 1200);
-		assert_eq!(two("50...\n..*..\n...60"), 3000);
-		assert_eq!(two("...70\n..*..\n80..."), 5600);
+		assert_eq!(Solution::two("10*20"), 200);
+		assert_eq!(Solution::two("30.\n.*.\n.40"), 1200);
+		assert_eq!(Solution::two("50...\n..*..\n...60"), 3000);
+		assert_eq!(Solution::two("...70\n..*..\n80..."), 5600);
 	}
 
 	#[test]
 	fn two_triplet()
 	{
-		assert_eq!(two("1*2\n.3."), 0);
+		assert_eq!(Solution::two("1*2\n.3."), 0);
 	}
 }
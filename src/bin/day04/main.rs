@@ -1,35 +1,62 @@
 /**/
 
-use aoc2023::{ring_buffer::RingBuffer, run};
+use aoc2023::parse::{self, ParseError};
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, ring_buffer::RingBuffer, run};
 use smallvec::SmallVec;
 
-const INPUT: &str = include_str!("input.txt");
-
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
+}
+
+pub struct Solution;
+
+impl Solver for Solution
+{
+	type Parsed<'a> = Vec<Card>;
+	type Answer = u32;
+
+	fn parse(input: &str) -> Result<Vec<Card>, ParseError>
+	{
+		parse::lines(input)
+			.map(|line| parse_card(input, line))
+			.collect()
+	}
+
+	fn part_one(cards: &Vec<Card>) -> u32
+	{
+		cards.iter().map(Card::score_points).sum()
+	}
+
+	fn part_two(cards: &Vec<Card>) -> u32
+	{
+		count_total_tickets(cards)
+	}
 }
 
-fn one(input: &str) -> i32
+#[derive(Debug)]
+pub struct Card
 {
-	input
-		.lines()
-		.filter(|line| !line.is_empty())
-		.map(decode_line_and_score_points)
-		.sum()
+	winning: SmallVec<[u8; 10]>,
+	own: SmallVec<[u8; 32]>,
 }
 
-fn decode_line_and_score_points(line: &str) -> i32
+impl Card
 {
-	let (_, data) = line.split_once(':').unwrap();
-	let (windata, owndata) = data.split_once('|').unwrap();
-	let winning: SmallVec<[u8; 10]> =
-		parse_stream_of_numbers(windata).collect();
-	let mut score = 0;
-	for own in parse_stream_of_numbers(owndata)
+	fn num_matches(&self) -> usize
 	{
-		if winning.contains(&own)
+		self.own
+			.iter()
+			.filter(|own| self.winning.contains(own))
+			.count()
+	}
+
+	fn score_points(&self) -> u32
+	{
+		let mut score = 0;
+		for _ in 0..self.num_matches()
 		{
 			if score == 0
 			{
@@ -40,8 +67,43 @@ fn decode_line_and_score_points(line: &str) -> i32
 				score *= 2;
 			}
 		}
+		score
 	}
-	score
+}
+
+fn parse_card(input: &str, line: &str) -> Result<Card, ParseError>
+{
+	let (_, data) = line
+		.split_once(':')
+		.ok_or_else(|| ParseError::after(input, line, "expected ':'"))?;
+	let (windata, owndata) = data
+		.split_once('|')
+		.ok_or_else(|| ParseError::after(input, line, "expected '|'"))?;
+	for numbers in [windata, owndata]
+	{
+		if let Some(i) = numbers.find(|x: char| !x.is_ascii_digit() && x != ' ')
+		{
+			return Err(ParseError::at(
+				input,
+				&numbers[i..],
+				"expected a digit or a space",
+			));
+		}
+		if let Some(number) = numbers
+			.split(' ')
+			.find(|x| !x.is_empty() && x.parse::<u8>().is_err())
+		{
+			return Err(ParseError::at(
+				input,
+				number,
+				"expected a number below 256",
+			));
+		}
+	}
+	Ok(Card {
+		winning: parse_stream_of_numbers(windata).collect(),
+		own: parse_stream_of_numbers(owndata).collect(),
+	})
 }
 
 fn parse_stream_of_numbers(data: &str) -> impl Iterator<Item = u8> + '_
@@ -67,12 +129,11 @@ fn parse_stream_of_numbers(data: &str) -> impl Iterator<Item = u8> + '_
 	})
 }
 
-fn two(input: &str) -> u32
+fn count_total_tickets(cards: &[Card]) -> u32
 {
-	let lines = input.lines().filter(|line| !line.is_empty());
 	let mut total_tickets = 0;
 	let mut multipliers: RingBuffer<[u32; 12]> = RingBuffer::default();
-	for line in lines
+	for card in cards
 	{
 		let own_multiplier = if let Some(&multiplier) = multipliers.head()
 		{
@@ -85,13 +146,7 @@ fn two(input: &str) -> u32
 		};
 		total_tickets += own_multiplier;
 
-		let (_, data) = line.split_once(':').unwrap();
-		let (windata, owndata) = data.split_once('|').unwrap();
-		let winning: SmallVec<[u8; 10]> =
-			parse_stream_of_numbers(windata).collect();
-		let num_matches = parse_stream_of_numbers(owndata)
-			.filter(|own| winning.contains(own))
-			.count();
+		let num_matches = card.num_matches();
 		while multipliers.len() < num_matches
 		{
 			multipliers.push(1);
@@ -115,12 +170,12 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 13);
+		assert_eq!(Solution::one(PROVIDED), 13);
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 30);
+		assert_eq!(Solution::two(PROVIDED), 30);
 	}
 }
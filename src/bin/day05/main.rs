@@ -1,131 +1,140 @@
 /**/
 
-use aoc2023::run;
+use aoc2023::parse::{self, ParseError};
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 use itertools::Itertools;
 use parse_display::{Display, FromStr};
 use smallvec::SmallVec;
 
-const INPUT: &str = include_str!("input.txt");
-
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> i64
+pub struct Solution;
+
+impl Solver for Solution
 {
-	let mut lines = input.lines();
-	let header = lines.next().unwrap();
-	let (_, seeds) = header.split_once(':').unwrap();
-	let seeds: SmallVec<[i64; 32]> = seeds
-		.split(' ')
-		.map(|word| word.trim())
-		.filter(|word| !word.is_empty())
-		.map(|word| word.parse().unwrap())
-		.collect();
-	let mut curr = seeds;
-	let mut next: SmallVec<[i64; 32]> = SmallVec::default();
-	for line in lines
+	type Parsed<'a> = Almanac;
+	type Answer = i64;
+
+	fn parse(input: &str) -> Result<Almanac, ParseError>
 	{
-		if line
-			.as_bytes()
-			.get(0)
-			.map_or(false, |&x| x.is_ascii_digit())
+		let mut lines = input.lines();
+		let header = lines.next().unwrap_or_default();
+		let (_, seeds) = header
+			.split_once(':')
+			.ok_or_else(|| ParseError::after(input, header, "expected ':'"))?;
+		let seeds: SmallVec<_> = seeds
+			.split(' ')
+			.filter(|word| !word.is_empty())
+			.map(|word| parse::parse_word(input, word, "a number"))
+			.collect::<Result<_, _>>()?;
+		if seeds.len() % 2 != 0
 		{
-			let transform: MapRow = line.parse().unwrap();
-			let is_transformed = |&x: &i64| {
-				x >= transform.source_start
-					&& x < transform.source_start + transform.len
-			};
-			curr.sort_unstable_by_key(is_transformed);
-			let drain_offset = curr.partition_point(|x| !is_transformed(x));
-			let matched = curr.drain(drain_offset..);
-			let mapped = matched.map(|x| transform.apply(x));
-			next.extend(mapped);
+			return Err(ParseError::after(
+				input,
+				header,
+				"expected an even number of seeds",
+			));
 		}
-		else
+		let mut maps: Vec<Vec<MapRow>> = Vec::new();
+		for line in lines
 		{
-			curr.append(&mut next);
+			if line.as_bytes().first().is_some_and(|x| x.is_ascii_digit())
+			{
+				let map = maps.last_mut().ok_or_else(|| {
+					ParseError::at(input, line, "expected a map header first")
+				})?;
+				map.push(line.parse().map_err(|_| {
+					ParseError::at(
+						input,
+						line,
+						"expected '<destination> <source> <length>'",
+					)
+				})?);
+			}
+			else if !line.is_empty()
+			{
+				maps.push(Vec::new());
+			}
 		}
+		Ok(Almanac { seeds, maps })
 	}
-	curr.append(&mut next);
-	curr.into_iter().min().unwrap()
-}
 
-#[derive(Display, FromStr)]
-#[display("{destination_start} {source_start} {len}")]
-struct MapRow
-{
-	destination_start: i64,
-	source_start: i64,
-	len: i64,
-}
-
-impl MapRow
-{
-	fn apply(&self, x: i64) -> i64
+	fn part_one(almanac: &Almanac) -> i64
 	{
-		x - self.source_start + self.destination_start
+		let mut curr = almanac.seeds.clone();
+		let mut next: SmallVec<[i64; 32]> = SmallVec::default();
+		for map in &almanac.maps
+		{
+			for transform in map
+			{
+				let is_transformed = |&x: &i64| {
+					x >= transform.source_start
+						&& x < transform.source_start + transform.len
+				};
+				curr.sort_unstable_by_key(is_transformed);
+				let drain_offset = curr.partition_point(|x| !is_transformed(x));
+				let matched = curr.drain(drain_offset..);
+				let mapped = matched.map(|x| transform.apply(x));
+				next.extend(mapped);
+			}
+			curr.append(&mut next);
+		}
+		curr.into_iter().min().unwrap()
 	}
-}
 
-fn two(input: &str) -> i64
-{
-	let mut lines = input.lines();
-	let header = lines.next().unwrap();
-	let (_, seeds) = header.split_once(':').unwrap();
-	let seed_ranges: SmallVec<[SeedRange; 32]> = seeds
-		.split(' ')
-		.map(|word| word.trim())
-		.filter(|word| !word.is_empty())
-		.map(|word| word.parse().unwrap())
-		.chunks(2)
-		.into_iter()
-		.map(|mut chunk| (chunk.next().unwrap(), chunk.next().unwrap()))
-		.map(|(start, len)| SeedRange {
-			start,
-			end: start + len,
-		})
-		.collect();
-
-	let mut curr = seed_ranges;
-	let mut next: SmallVec<[SeedRange; 32]> = SmallVec::default();
-	for line in lines
+	fn part_two(almanac: &Almanac) -> i64
 	{
-		if line
-			.as_bytes()
-			.get(0)
-			.map_or(false, |&x| x.is_ascii_digit())
+		let seed_ranges: SmallVec<[SeedRange; 32]> = almanac
+			.seeds
+			.iter()
+			.copied()
+			.chunks(2)
+			.into_iter()
+			.map(|mut chunk| (chunk.next().unwrap(), chunk.next().unwrap()))
+			.map(|(start, len)| SeedRange {
+				start,
+				end: start + len,
+			})
+			.collect();
+
+		let mut curr = seed_ranges;
+		let mut next: SmallVec<[SeedRange; 32]> = SmallVec::default();
+		for map in &almanac.maps
 		{
-			let transform: MapRow = line.parse().unwrap();
-			let transform_range = SeedRange::from(&transform);
-			let is_touched = |&x: &SeedRange| transform_range.intersects(x);
-			curr.sort_unstable_by_key(is_touched);
-			let drain_offset = curr.partition_point(|x| !is_touched(x));
-			let matched = curr.drain(drain_offset..);
-			let mut untouched: SmallVec<[SeedRange; 32]> = SmallVec::default();
-			for range in matched
+			for transform in map
 			{
-				let (before, inside, after) = range.cut(transform_range);
-				if before.len() > 0
+				let transform_range = SeedRange::from(transform);
+				let is_touched = |&x: &SeedRange| transform_range.intersects(x);
+				curr.sort_unstable_by_key(is_touched);
+				let drain_offset = curr.partition_point(|x| !is_touched(x));
+				let matched = curr.drain(drain_offset..);
+				let mut untouched: SmallVec<[SeedRange; 32]> =
+					SmallVec::default();
+				for range in matched
 				{
-					untouched.push(before);
-				}
-				if after.len() > 0
-				{
-					untouched.push(after);
+					let (before, inside, after) = range.cut(transform_range);
+					if before.len() > 0
+					{
+						untouched.push(before);
+					}
+					if after.len() > 0
+					{
+						untouched.push(after);
+					}
+					let start = transform.apply(inside.start);
+					next.push(SeedRange {
+						start,
+						end: start + inside.len(),
+					});
 				}
-				let start = transform.apply(inside.start);
-				next.push(SeedRange {
-					start,
-					end: start + inside.len(),
-				});
+				curr.append(&mut untouched);
 			}
-			curr.append(&mut untouched);
-		}
-		else
-		{
+
 			next.append(&mut curr);
 			next.sort_unstable_by_key(|range| range.start);
 			for range in next.drain(..)
@@ -147,9 +156,32 @@ fn two(input: &str) -> i64
 				}
 			}
 		}
+		curr.into_iter().map(|range| range.start).min().unwrap()
+	}
+}
+
+#[derive(Debug)]
+pub struct Almanac
+{
+	seeds: SmallVec<[i64; 32]>,
+	maps: Vec<Vec<MapRow>>,
+}
+
+#[derive(Debug, Display, FromStr)]
+#[display("{destination_start} {source_start} {len}")]
+struct MapRow
+{
+	destination_start: i64,
+	source_start: i64,
+	len: i64,
+}
+
+impl MapRow
+{
+	fn apply(&self, x: i64) -> i64
+	{
+		x - self.source_start + self.destination_start
 	}
-	curr.append(&mut next);
-	curr.into_iter().map(|range| range.start).min().unwrap()
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -224,12 +256,12 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 35);
+		assert_eq!(Solution::one(PROVIDED), 35);
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 46);
+		assert_eq!(Solution::two(PROVIDED), 46);
 	}
 }
@@ -1,33 +1,84 @@
 /**/
 
 use aoc2023::binary_search_range;
-use aoc2023::run;
-
-const INPUT: &str = include_str!("input.txt");
+use aoc2023::parse::{self, ParseError};
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> i64
+pub struct Solution;
+
+impl Solver for Solution
 {
-	let mut lines = input.lines();
-	let (_, time_line) = lines.next().unwrap().split_once(':').unwrap();
-	let (_, distance_line) = lines.next().unwrap().split_once(':').unwrap();
-	let times = time_line
-		.split(' ')
-		.map(|x| x.trim())
-		.filter(|x| !x.is_empty())
-		.map(|x| x.parse().unwrap());
-	let distances = distance_line
+	type Parsed<'a> = Vec<Race>;
+	type Answer = i64;
+
+	fn parse(input: &str) -> Result<Vec<Race>, ParseError>
+	{
+		let mut lines = input.lines();
+		let time_line = lines.next().unwrap_or_default();
+		let distance_line = lines.next().unwrap_or_default();
+		let times = parse_numbers(input, time_line)?;
+		let distances = parse_numbers(input, distance_line)?;
+		if times.len() != distances.len()
+		{
+			return Err(ParseError::after(
+				input,
+				distance_line,
+				format!("expected {} distances, one per time", times.len()),
+			));
+		}
+		Ok(times
+			.into_iter()
+			.zip(distances)
+			.map(|(time, distance)| Race { time, distance })
+			.collect())
+	}
+
+	fn part_one(races: &Vec<Race>) -> i64
+	{
+		races
+			.iter()
+			.map(|race| win_race(race.time, race.distance))
+			.product()
+	}
+
+	fn part_two(races: &Vec<Race>) -> i64
+	{
+		let time = unkern(races.iter().map(|race| race.time));
+		let distance = unkern(races.iter().map(|race| race.distance));
+		let root = (distance as f64).sqrt().ceil() as i64;
+		let start = binary_search_range(0..root, |t| t * (time - t) > distance);
+		let end =
+			binary_search_range(root..time, |t| t * (time - t) <= distance);
+		let start = start.unwrap();
+		let end = end.unwrap();
+		end - start
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Race
+{
+	time: i64,
+	distance: i64,
+}
+
+fn parse_numbers(input: &str, line: &str) -> Result<Vec<i64>, ParseError>
+{
+	let (_, numbers) = line
+		.split_once(':')
+		.ok_or_else(|| ParseError::after(input, line, "expected ':'"))?;
+	numbers
 		.split(' ')
-		.map(|x| x.trim())
 		.filter(|x| !x.is_empty())
-		.map(|x| x.parse().unwrap());
-	let races = times.zip(distances);
-	races.map(|(t, d)| win_race(t, d)).product()
+		.map(|x| parse::parse_word(input, x, "a number"))
+		.collect()
 }
 
 fn win_race(time: i64, distance: i64) -> i64
@@ -49,32 +100,19 @@ fn win_race(time: i64, distance: i64) -> i64
 	num_possibilities
 }
 
-fn two(input: &str) -> i64
-{
-	let mut lines = input.lines();
-	let time = parse_badly_kerned_number(lines.next().unwrap());
-	let distance = parse_badly_kerned_number(lines.next().unwrap());
-	let root = (distance as f64).sqrt().ceil() as i64;
-	let start = binary_search_range(0..root, |t| t * (time - t) > distance);
-	let end = binary_search_range(root..time, |t| t * (time - t) <= distance);
-	let start = start.unwrap();
-	let end = end.unwrap();
-	end - start
-}
-
-fn parse_badly_kerned_number(line: &str) -> i64
+fn unkern(numbers: impl Iterator<Item = i64>) -> i64
 {
-	let (_, line) = line.split_once(':').unwrap();
-	let mut number = 0;
-	for x in line.as_bytes()
+	let mut result = 0;
+	for number in numbers
 	{
-		if x.is_ascii_digit()
+		let mut shift = 10;
+		while shift <= number
 		{
-			number *= 10;
-			number += i64::from(x - b'0');
+			shift *= 10;
 		}
+		result = result * shift + number;
 	}
-	number
+	result
 }
 
 #[cfg(test)]
@@ -88,12 +126,12 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 288);
+		assert_eq!(Solution::one(PROVIDED), 288);
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 71503);
+		assert_eq!(Solution::two(PROVIDED), 71503);
 	}
 }
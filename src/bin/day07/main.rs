@@ -1,25 +1,78 @@
 /**/
 
-use aoc2023::run;
+use aoc2023::parse::{self, ParseError};
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 use smallvec::SmallVec;
 
-const INPUT: &str = include_str!("input.txt");
-
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
+}
+
+pub struct Solution;
+
+impl Solver for Solution
+{
+	type Parsed<'a> = Vec<Hand>;
+	type Answer = i64;
+
+	fn parse(input: &str) -> Result<Vec<Hand>, ParseError>
+	{
+		parse::lines(input)
+			.map(|line| {
+				let (labels, bid) = line.split_once(' ').ok_or_else(|| {
+					ParseError::after(input, line, "expected '<cards> <bid>'")
+				})?;
+				if let Some(i) = labels.find(|x| !"23456789TJQKA".contains(x))
+				{
+					return Err(ParseError::at(
+						input,
+						&labels[i..],
+						"expected a card out of 23456789TJQKA",
+					));
+				}
+				let mut cards = [0u8; 5];
+				if labels.len() != cards.len()
+				{
+					return Err(ParseError::at(
+						input,
+						labels,
+						"expected a hand of 5 cards",
+					));
+				}
+				cards.copy_from_slice(labels.as_bytes());
+				let bid = parse::parse_word(input, bid, "a bid")?;
+				Ok(Hand { cards, bid })
+			})
+			.collect()
+	}
+
+	fn part_one(hands: &Vec<Hand>) -> i64
+	{
+		solve(hands, card_from_ascii_v1)
+	}
+
+	fn part_two(hands: &Vec<Hand>) -> i64
+	{
+		solve(hands, card_from_ascii_v2)
+	}
 }
 
-fn one(input: &str) -> i64
+#[derive(Debug, Clone, Copy)]
+pub struct Hand
 {
-	solve(input, parse_hand_v1)
+	cards: [u8; 5],
+	bid: u16,
 }
 
-fn solve(input: &str, parse_hand: impl Fn(&str) -> u64) -> i64
+fn solve(hands: &[Hand], card_from_ascii: impl Fn(u8) -> u8) -> i64
 {
-	let lines = input.lines().filter(|x| !x.is_empty());
-	let mut hands: SmallVec<[u64; 1024]> = lines.map(parse_hand).collect();
+	let mut hands: SmallVec<[u64; 1024]> = hands
+		.iter()
+		.map(|hand| encode_hand(hand, &card_from_ascii))
+		.collect();
 	debug_assert!(hands.len() < 1024);
 	hands.sort_unstable();
 	hands
@@ -29,27 +82,11 @@ fn solve(input: &str, parse_hand: impl Fn(&str) -> u64) -> i64
 		.sum()
 }
 
-fn parse_hand_v1(input: &str) -> u64
-{
-	parse_hand(input, card_from_ascii_v1)
-}
-
-fn parse_hand_v2(input: &str) -> u64
-{
-	parse_hand(input, card_from_ascii_v2)
-}
-
-fn parse_hand(input: &str, card_from_ascii: impl Fn(u8) -> u8) -> u64
+fn encode_hand(hand: &Hand, card_from_ascii: impl Fn(u8) -> u8) -> u64
 {
-	let bytes = input.as_bytes();
-	let mut cards = [0u8; 5];
-	for i in 0..5
-	{
-		cards[i] = card_from_ascii(bytes[i]);
-	}
+	let cards = hand.cards.map(card_from_ascii);
 	let hand_kind: u8 = determine_hand(&cards);
-	let bid = parse_bid(&bytes[6..]);
-	hand_from_parts(cards, hand_kind, bid)
+	hand_from_parts(cards, hand_kind, hand.bid)
 }
 
 fn hand_from_parts(cards: [u8; 5], hand_kind: u8, bid: u16) -> u64
@@ -67,20 +104,6 @@ fn hand_from_parts(cards: [u8; 5], hand_kind: u8, bid: u16) -> u64
 	hand
 }
 
-fn parse_bid(bytes: &[u8]) -> u16
-{
-	let mut bid: u16 = 0;
-	for &x in bytes
-	{
-		if x.is_ascii_digit()
-		{
-			bid *= 10;
-			bid += u16::from(x - b'0');
-		}
-	}
-	bid
-}
-
 fn card_from_ascii_v1(x: u8) -> u8
 {
 	match x
@@ -91,7 +114,7 @@ fn card_from_ascii_v1(x: u8) -> u8
 		b'Q' => 0xC,
 		b'K' => 0xD,
 		b'A' => 0xE,
-		_ => panic!("Unexpected character token: '{x}'"),
+		_ => unreachable!("rejected by parse"),
 	}
 }
 
@@ -105,7 +128,7 @@ fn card_from_ascii_v2(x: u8) -> u8
 		b'Q' => 0xC,
 		b'K' => 0xD,
 		b'A' => 0xE,
-		_ => panic!("Unexpected character token: '{x}'"),
+		_ => unreachable!("rejected by parse"),
 	}
 }
 
@@ -198,11 +221,6 @@ fn bid_from_hand(hand: u64) -> i64
 	i64::from(bid)
 }
 
-fn two(input: &str) -> i64
-{
-	solve(input, parse_hand_v2)
-}
-
 #[cfg(test)]
 mod tests
 {
@@ -214,7 +232,7 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 6440);
+		assert_eq!(Solution::one(PROVIDED), 6440);
 	}
 
 	#[test]
@@ -245,6 +263,6 @@ mod tests
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 5905);
+		assert_eq!(Solution::two(PROVIDED), 5905);
 	}
 }
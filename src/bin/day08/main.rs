@@ -1,35 +1,82 @@
 /**/
 
-use aoc2023::run;
-
-const INPUT: &str = include_str!("input.txt");
+use aoc2023::dot::Dot;
+use aoc2023::math::lcm;
+use aoc2023::parse::{self, ParseError};
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> usize
+pub struct Solution;
+
+impl Solver for Solution
 {
-	let (instructions, rest) = input.split_once('\n').unwrap();
-	let graph = Graph::from_input(rest);
-	let mut i = 0;
-	let mut instructions = instructions.as_bytes().iter().cycle();
-	let mut num_steps_taken = 0;
-	while i < NUM_NAMES - 1
+	type Parsed<'a> = Network<'a>;
+	type Answer = usize;
+
+	fn parse(input: &str) -> Result<Network<'_>, ParseError>
 	{
-		if instructions.next().unwrap() == &b'L'
+		let (instructions, rest) = input.split_once('\n').unwrap_or_default();
+		if instructions.is_empty()
 		{
-			i = graph.lefts[i] as usize;
+			return Err(ParseError::new(1, 1, "expected instructions"));
 		}
-		else
+		if let Some(i) = instructions.find(|x| x != 'L' && x != 'R')
+		{
+			return Err(ParseError::at(
+				input,
+				&instructions[i..],
+				"expected 'L' or 'R'",
+			));
+		}
+		Ok(Network {
+			instructions: instructions.as_bytes(),
+			graph: Graph::from_input(input, rest)?,
+		})
+	}
+
+	fn part_one(network: &Network) -> usize
+	{
+		let graph = &network.graph;
+		let mut i = 0;
+		let mut instructions = network.instructions.iter().cycle();
+		let mut num_steps_taken = 0;
+		while i < NUM_NAMES - 1
 		{
-			i = graph.rights[i] as usize;
+			if instructions.next().unwrap() == &b'L'
+			{
+				i = graph.lefts[i] as usize;
+			}
+			else
+			{
+				i = graph.rights[i] as usize;
+			}
+			num_steps_taken += 1;
 		}
-		num_steps_taken += 1;
+		num_steps_taken
+	}
+
+	fn part_two(network: &Network) -> usize
+	{
+		count_ghost_steps(network)
+	}
+
+	fn to_dot(network: &Network) -> Option<Dot>
+	{
+		Some(network.graph.to_dot())
 	}
-	num_steps_taken
+}
+
+#[derive(Debug)]
+pub struct Network<'a>
+{
+	instructions: &'a [u8],
+	graph: Graph,
 }
 
 const NUM_NAMES: usize = 26 * 26 * 26;
@@ -41,47 +88,104 @@ fn encode_name(name: &[u8]) -> u16
 		.fold(0, |x, c| x * 26 + u16::from(c - b'A'))
 }
 
+fn decode_name(i: u16) -> String
+{
+	let mut name = String::with_capacity(3);
+	let mut x = i;
+	for _ in 0..3
+	{
+		name.push(char::from(b'A' + (x % 26) as u8));
+		x /= 26;
+	}
+	name
+}
+
 #[derive(Debug)]
 struct Graph
 {
 	lefts: [u16; NUM_NAMES],
 	rights: [u16; NUM_NAMES],
+	/// The nodes that have a line of their own, in input order.
+	defined: Vec<u16>,
 }
 
 impl Graph
 {
-	fn from_input(input: &str) -> Graph
+	fn from_input(input: &str, nodes: &str) -> Result<Graph, ParseError>
 	{
-		let lines = input.lines().filter(|x| !x.is_empty());
 		let mut graph = Graph {
 			lefts: [0; NUM_NAMES],
 			rights: [0; NUM_NAMES],
+			defined: Vec::new(),
 		};
 		for i in 0..MAX_NUM_GHOSTS
 		{
 			graph.lefts[i] = i as u16;
 			graph.rights[i] = i as u16;
 		}
-		for line in lines
+		for line in parse::lines(nodes)
 		{
-			let line = line.as_bytes();
+			let is_name = |x: &[u8]| x.iter().all(u8::is_ascii_uppercase);
+			let bytes = line.as_bytes();
+			let is_valid = bytes.len() == 16
+				&& is_name(&bytes[0..3])
+				&& &bytes[3..7] == b" = ("
+				&& is_name(&bytes[7..10])
+				&& &bytes[10..12] == b", "
+				&& is_name(&bytes[12..15])
+				&& bytes[15] == b')';
+			if !is_valid
+			{
+				return Err(ParseError::at(
+					input,
+					line,
+					"expected 'AAA = (BBB, CCC)'",
+				));
+			}
+			let line = bytes;
 			let cur = encode_name(&line[0..3]);
 			let left = encode_name(&line[7..10]);
 			let right = encode_name(&line[12..15]);
 			let i = cur as usize;
 			graph.lefts[i] = left;
 			graph.rights[i] = right;
+			graph.defined.push(cur);
+		}
+		Ok(graph)
+	}
+
+	/// Starts (`..A`) are green and ends (`..Z`) red, as in part two.
+	fn to_dot(&self) -> Dot
+	{
+		let mut dot = Dot::digraph("day08");
+		for &i in &self.defined
+		{
+			let name = decode_name(i);
+			let node = dot.node(&name);
+			match name.as_bytes()[2]
+			{
+				b'A' => node.color("green"),
+				b'Z' => node.color("red"),
+				_ => node,
+			};
 		}
-		graph
+		for &i in &self.defined
+		{
+			let name = decode_name(i);
+			let (left, right) =
+				(self.lefts[i as usize], self.rights[i as usize]);
+			dot.edge(&name, decode_name(left)).label("L");
+			dot.edge(&name, decode_name(right)).label("R");
+		}
+		dot
 	}
 }
 
 const MAX_NUM_GHOSTS: usize = 26 * 26;
 
-fn two(input: &str) -> usize
+fn count_ghost_steps(network: &Network) -> usize
 {
-	let (instructions, rest) = input.split_once('\n').unwrap();
-	let graph = Graph::from_input(rest);
+	let graph = &network.graph;
 
 	let first_end = (NUM_NAMES - MAX_NUM_GHOSTS) as u16;
 
@@ -104,7 +208,7 @@ fn two(input: &str) -> usize
 	for g in 0..num_ghosts
 	{
 		let mut i = ghosts[g];
-		let mut instructions = instructions.as_bytes().iter().cycle();
+		let mut instructions = network.instructions.iter().cycle();
 		let mut num_steps_taken = 0;
 		while i < first_end
 		{
@@ -124,26 +228,6 @@ fn two(input: &str) -> usize
 	answer
 }
 
-fn lcm(a: usize, b: usize) -> usize
-{
-	a / gcd(a, b) * b
-}
-
-fn gcd(mut a: usize, mut b: usize) -> usize
-{
-	if a < b
-	{
-		return gcd(b, a);
-	}
-	while b != 0
-	{
-		let c = a % b;
-		a = b;
-		b = c;
-	}
-	a
-}
-
 #[cfg(test)]
 mod tests
 {
@@ -174,29 +258,41 @@ mod tests
 			usize::from(encode_name("AAZ".as_bytes())),
 			NUM_NAMES - MAX_NUM_GHOSTS
 		);
+		assert_eq!(decode_name(encode_name("XYZ".as_bytes())), "XYZ");
+	}
+
+	#[test]
+	fn test_to_dot()
+	{
+		let network = Solution::parse(PROVIDED2).unwrap();
+		let dot = Solution::to_dot(&network).unwrap();
+		assert_eq!((dot.num_nodes(), dot.num_edges()), (3, 6));
+		assert!(dot
+			.to_string()
+			.contains("\t\"BBB\" -> \"ZZZ\" [label=\"R\"];\n"));
 	}
 
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 2);
+		assert_eq!(Solution::one(PROVIDED), 2);
 	}
 
 	#[test]
 	fn one_provided_shuffled()
 	{
-		assert_eq!(one(PROVIDED_SHUFFLED), 2);
+		assert_eq!(Solution::one(PROVIDED_SHUFFLED), 2);
 	}
 
 	#[test]
 	fn one_provided2()
 	{
-		assert_eq!(one(PROVIDED2), 6);
+		assert_eq!(Solution::one(PROVIDED2), 6);
 	}
 
 	#[test]
 	fn two_provided_two()
 	{
-		assert_eq!(two(PROVIDED_TWO), 6);
+		assert_eq!(Solution::two(PROVIDED_TWO), 6);
 	}
 }
@@ -1,31 +1,53 @@
 /**/
 
-use aoc2023::run;
-
-const INPUT: &str = include_str!("input.txt");
+use aoc2023::parse::{self, ParseError};
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
+use smallvec::SmallVec;
 
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> i32
+pub struct Solution;
+
+impl Solver for Solution
 {
-	input
-		.lines()
-		.filter(|x| !x.is_empty())
-		.map(solve_naive)
-		.sum()
+	type Parsed<'a> = Vec<SmallVec<[i32; 32]>>;
+	type Answer = i32;
+
+	fn parse(input: &str) -> Result<Vec<SmallVec<[i32; 32]>>, ParseError>
+	{
+		parse::lines(input)
+			.map(|line| {
+				line.split(' ')
+					.map(|x| parse::parse_word(input, x, "a number"))
+					.collect()
+			})
+			.collect()
+	}
+
+	fn part_one(sequences: &Vec<SmallVec<[i32; 32]>>) -> i32
+	{
+		sequences.iter().map(|numbers| solve_naive(numbers)).sum()
+	}
+
+	fn part_two(sequences: &Vec<SmallVec<[i32; 32]>>) -> i32
+	{
+		sequences
+			.iter()
+			.map(|numbers| solve_naive_rev(numbers))
+			.sum()
+	}
 }
 
-fn solve_naive(line: &str) -> i32
+fn solve_naive(numbers: &[i32]) -> i32
 {
-	let numbers = line.split(' ').map(|x| x.parse().unwrap());
-
 	let mut data = [[0i32; 32]; 32];
 	let mut len = 0;
-	for number in numbers
+	for &number in numbers
 	{
 		data[0][len] = number;
 		len += 1;
@@ -73,22 +95,11 @@ fn print_data(data: &[[i32; 32]; 32], depth: usize, len: usize)
 	println!();
 }
 
-fn two(input: &str) -> i32
+fn solve_naive_rev(numbers: &[i32]) -> i32
 {
-	input
-		.lines()
-		.filter(|x| !x.is_empty())
-		.map(solve_naive_rev)
-		.sum()
-}
-
-fn solve_naive_rev(line: &str) -> i32
-{
-	let numbers = line.split(' ').map(|x| x.parse().unwrap());
-
 	let mut data = [[0i32; 32]; 32];
 	let mut len = 0;
-	for number in numbers
+	for &number in numbers
 	{
 		len += 1;
 		data[0][len] = number;
@@ -126,12 +137,12 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 114);
+		assert_eq!(Solution::one(PROVIDED), 114);
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 2);
+		assert_eq!(Solution::two(PROVIDED), 2);
 	}
 }
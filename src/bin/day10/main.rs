@@ -1,64 +1,88 @@
 /**/
 
-use aoc2023::run;
-
-const INPUT: &str = include_str!("input.txt");
-
-const GRID_SIZE: usize = 192;
+use aoc2023::geometry::{
+	self,
+	Direction::{Down, Left, Right, Up},
+};
+use aoc2023::grid::Grid;
+use aoc2023::parse::ParseError;
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn parse_input(
-	input: &str,
-	grid: &mut [[u8; GRID_SIZE]; GRID_SIZE],
-	start: &mut Point,
-)
+pub struct Solution;
+
+impl Solver for Solution
 {
-	for (i, line) in input.lines().filter(|x| !x.is_empty()).enumerate()
+	type Parsed<'a> = Maze;
+	type Answer = usize;
+
+	fn parse(input: &str) -> Result<Maze, ParseError>
 	{
-		let row = i + 1;
-		let cols = 1..(line.as_bytes().len() + 1);
-		grid[row][cols].copy_from_slice(line.as_bytes());
-		if let Some(j) = line.as_bytes().iter().position(|&x| x == b'S')
-		{
-			start.row = row;
-			start.col = j + 1;
-		}
+		let grid = Grid::try_parse(input, "a pipe, '.' or 'S'", |x| {
+			"|-LJ7F.S".contains(x).then_some(x as u8)
+		})?;
+		// The border keeps every neighbour of a pipe inside the grid.
+		let grid = grid.with_border(1, 0);
+		let (row, col) = grid.position(|&x| x == b'S').ok_or_else(|| {
+			ParseError::new(1, 1, "expected a starting tile 'S'")
+		})?;
+		Ok(Maze {
+			grid,
+			start: Point { row, col },
+		})
+	}
+
+	fn part_one(maze: &Maze) -> usize
+	{
+		find_farthest_point(maze)
+	}
+
+	fn part_two(maze: &Maze) -> usize
+	{
+		count_enclosed_tiles(maze)
 	}
 }
 
-fn get_probes(
+#[derive(Debug)]
+pub struct Maze
+{
+	grid: Grid<u8>,
 	start: Point,
-	grid: &[[u8; GRID_SIZE]; GRID_SIZE],
-) -> (Probe, Probe)
+}
+
+fn get_probes(start: Point, grid: &Grid<u8>) -> (Probe, Probe)
 {
-	let mut probes = [start.right(), start.up(), start.left(), start.down()]
-		.into_iter()
-		.filter(|point| point.adjacents(&grid).contains(&start))
-		.map(|point| Probe {
-			curr: point,
-			prev: start,
-		});
+	let mut probes = [
+		start.step(Right),
+		start.step(Up),
+		start.step(Left),
+		start.step(Down),
+	]
+	.into_iter()
+	.filter(|&point| adjacents(grid, point).contains(&start))
+	.map(|point| Probe {
+		curr: point,
+		prev: start,
+	});
 	let a = probes.next().unwrap();
 	let b = probes.next().unwrap();
 	(a, b)
 }
 
-fn one(input: &str) -> usize
+fn find_farthest_point(maze: &Maze) -> usize
 {
-	let mut grid = [[0u8; GRID_SIZE]; GRID_SIZE];
-	let mut start = Point::default();
-	parse_input(input, &mut grid, &mut start);
-
-	let (mut a, mut b) = get_probes(start, &grid);
-	for round in 2..(GRID_SIZE * GRID_SIZE)
+	let grid = &maze.grid;
+	let (mut a, mut b) = get_probes(maze.start, grid);
+	for round in 2..(grid.width() * grid.height())
 	{
-		a = a.step(&grid);
-		b = b.step(&grid);
+		a = a.step(grid);
+		b = b.step(grid);
 
 		if a.curr == b.curr
 		{
@@ -68,69 +92,29 @@ fn one(input: &str) -> usize
 	unreachable!()
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq)]
-struct Point
+type Point = geometry::Point<usize>;
+
+fn pipe_at(grid: &Grid<u8>, point: Point) -> u8
 {
-	row: usize,
-	col: usize,
+	grid[point.row][point.col]
 }
 
-impl Point
+fn adjacents(grid: &Grid<u8>, point: Point) -> [Point; 2]
 {
-	fn up(self) -> Self
-	{
-		Self {
-			row: self.row - 1,
-			col: self.col,
-		}
-	}
-
-	fn down(self) -> Self
-	{
-		Self {
-			row: self.row + 1,
-			col: self.col,
-		}
-	}
-
-	fn left(self) -> Self
-	{
-		Self {
-			row: self.row,
-			col: self.col - 1,
-		}
-	}
-
-	fn right(self) -> Self
-	{
-		Self {
-			row: self.row,
-			col: self.col + 1,
-		}
-	}
-
-	fn pipe(&self, grid: &[[u8; GRID_SIZE]; GRID_SIZE]) -> u8
-	{
-		grid[self.row][self.col]
-	}
-
-	fn adjacents(&self, grid: &[[u8; GRID_SIZE]; GRID_SIZE]) -> [Point; 2]
-	{
-		self.adjacents_for_pipe(self.pipe(grid))
-	}
+	adjacents_for_pipe(point, pipe_at(grid, point))
+}
 
-	fn adjacents_for_pipe(&self, pipe: u8) -> [Point; 2]
+fn adjacents_for_pipe(point: Point, pipe: u8) -> [Point; 2]
+{
+	match pipe
 	{
-		match pipe
-		{
-			b'|' => [self.up(), self.down()],
-			b'-' => [self.left(), self.right()],
-			b'L' => [self.up(), self.right()],
-			b'J' => [self.up(), self.left()],
-			b'7' => [self.down(), self.left()],
-			b'F' => [self.down(), self.right()],
-			_ => [*self, *self],
-		}
+		b'|' => [point.step(Up), point.step(Down)],
+		b'-' => [point.step(Left), point.step(Right)],
+		b'L' => [point.step(Up), point.step(Right)],
+		b'J' => [point.step(Up), point.step(Left)],
+		b'7' => [point.step(Down), point.step(Left)],
+		b'F' => [point.step(Down), point.step(Right)],
+		_ => [point, point],
 	}
 }
 
@@ -143,9 +127,9 @@ struct Probe
 
 impl Probe
 {
-	fn step(&self, grid: &[[u8; GRID_SIZE]; GRID_SIZE]) -> Probe
+	fn step(&self, grid: &Grid<u8>) -> Probe
 	{
-		let points = self.curr.adjacents(grid);
+		let points = adjacents(grid, self.curr);
 		let next = if points[0] != self.prev
 		{
 			points[0]
@@ -185,14 +169,14 @@ impl Painter
 {
 	fn step(
 		&self,
-		grid: &[[u8; GRID_SIZE]; GRID_SIZE],
-		wall: &mut [[u8; GRID_SIZE]; GRID_SIZE],
+		grid: &Grid<u8>,
+		wall: &mut Grid<u8>,
 		num_twos: &mut usize,
 		num_threes: &mut usize,
 	) -> Painter
 	{
 		let curr = self.curr;
-		let pipe = curr.pipe(grid);
+		let pipe = pipe_at(grid, curr);
 
 		match wall[curr.row][curr.col]
 		{
@@ -206,42 +190,42 @@ impl Painter
 
 		let exits = match pipe
 		{
-			b'|' => [curr.up(), curr.down()],
-			b'-' => [curr.right(), curr.left()],
-			b'L' => [curr.right(), curr.up()],
-			b'J' => [curr.up(), curr.left()],
-			b'7' => [curr.down(), curr.left()],
-			b'F' => [curr.right(), curr.down()],
+			b'|' => [curr.step(Up), curr.step(Down)],
+			b'-' => [curr.step(Right), curr.step(Left)],
+			b'L' => [curr.step(Right), curr.step(Up)],
+			b'J' => [curr.step(Up), curr.step(Left)],
+			b'7' => [curr.step(Down), curr.step(Left)],
+			b'F' => [curr.step(Right), curr.step(Down)],
 			_ => return *self,
 		};
 
 		let sides = match pipe
 		{
-			b'|' => [curr.left(), curr.right(), curr, curr],
-			b'-' => [curr.up(), curr.down(), curr, curr],
+			b'|' => [curr.step(Left), curr.step(Right), curr, curr],
+			b'-' => [curr.step(Up), curr.step(Down), curr, curr],
 			b'L' => [
-				curr.down().left(),
-				curr.up().right(),
-				curr.down(),
-				curr.left(),
+				curr.step(Down).step(Left),
+				curr.step(Up).step(Right),
+				curr.step(Down),
+				curr.step(Left),
 			],
 			b'J' => [
-				curr.down().right(),
-				curr.up().left(),
-				curr.down(),
-				curr.right(),
+				curr.step(Down).step(Right),
+				curr.step(Up).step(Left),
+				curr.step(Down),
+				curr.step(Right),
 			],
 			b'7' => [
-				curr.up().right(),
-				curr.down().left(),
-				curr.up(),
-				curr.right(),
+				curr.step(Up).step(Right),
+				curr.step(Down).step(Left),
+				curr.step(Up),
+				curr.step(Right),
 			],
 			b'F' => [
-				curr.up().left(),
-				curr.down().right(),
-				curr.up(),
-				curr.left(),
+				curr.step(Up).step(Left),
+				curr.step(Down).step(Right),
+				curr.step(Up),
+				curr.step(Left),
 			],
 			_ => return *self,
 		};
@@ -322,18 +306,17 @@ impl Painter
 	}
 }
 
-fn two(input: &str) -> usize
+fn count_enclosed_tiles(maze: &Maze) -> usize
 {
-	let mut grid = [[0u8; GRID_SIZE]; GRID_SIZE];
-	let mut start = Point::default();
-	parse_input(input, &mut grid, &mut start);
+	let grid = &maze.grid;
+	let start = maze.start;
 
-	let mut wall = [[0u8; GRID_SIZE]; GRID_SIZE];
+	let mut wall = Grid::new(grid.width(), grid.height(), 0u8);
 	let mut num_twos = 0;
 	let mut num_threes = 0;
 	wall[start.row][start.col] = 1;
 
-	let (a, b) = get_probes(start, &grid);
+	let (a, b) = get_probes(start, grid);
 	let mut a = Painter::from(a);
 	let mut b = Painter::from(b);
 	{
@@ -347,8 +330,8 @@ fn two(input: &str) -> usize
 
 	while a.curr != b.curr
 	{
-		a = a.step(&grid, &mut wall, &mut num_twos, &mut num_threes);
-		b = b.step(&grid, &mut wall, &mut num_twos, &mut num_threes);
+		a = a.step(grid, &mut wall, &mut num_twos, &mut num_threes);
+		b = b.step(grid, &mut wall, &mut num_twos, &mut num_threes);
 	}
 
 	match wall[a.curr.row][a.curr.col]
@@ -371,9 +354,9 @@ fn two(input: &str) -> usize
 	{
 		old_num_inside = num_inside;
 
-		for r in 1..(GRID_SIZE - 1)
+		for r in 1..(wall.height() - 1)
 		{
-			for c in 1..(GRID_SIZE - 1)
+			for c in 1..(wall.width() - 1)
 			{
 				if wall[r][c] != 0
 				{
@@ -404,17 +387,14 @@ fn two(input: &str) -> usize
 }
 
 #[allow(unused)]
-fn debug_grid_wall(
-	grid: &[[u8; GRID_SIZE]; GRID_SIZE],
-	wall: &[[u8; GRID_SIZE]; GRID_SIZE],
-) -> String
+fn debug_grid_wall(grid: &Grid<u8>, wall: &Grid<u8>) -> String
 {
 	use std::fmt::Write;
 	let mut output = String::new();
 	writeln!(&mut output);
-	for r in 0..GRID_SIZE
+	for r in 0..grid.height()
 	{
-		for c in 0..GRID_SIZE
+		for c in 0..grid.width()
 		{
 			let color = wall[r][c];
 			let x = match color
@@ -450,54 +430,54 @@ mod tests
 	#[test]
 	fn one_provided_clean()
 	{
-		assert_eq!(one(PROVIDED_CLEAN), 4);
+		assert_eq!(Solution::one(PROVIDED_CLEAN), 4);
 	}
 
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 4);
+		assert_eq!(Solution::one(PROVIDED), 4);
 	}
 
 	#[test]
 	fn one_provided_alt_clean()
 	{
-		assert_eq!(one(PROVIDED_ALT_CLEAN), 8);
+		assert_eq!(Solution::one(PROVIDED_ALT_CLEAN), 8);
 	}
 
 	#[test]
 	fn one_provided_alt()
 	{
-		assert_eq!(one(PROVIDED_ALT), 8);
+		assert_eq!(Solution::one(PROVIDED_ALT), 8);
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 1);
+		assert_eq!(Solution::two(PROVIDED), 1);
 	}
 
 	#[test]
 	fn two_provided_alt()
 	{
-		assert_eq!(two(PROVIDED_ALT), 1);
+		assert_eq!(Solution::two(PROVIDED_ALT), 1);
 	}
 
 	#[test]
 	fn two_provided_two_n()
 	{
-		assert_eq!(two(PROVIDED_TWO_N), 4);
+		assert_eq!(Solution::two(PROVIDED_TWO_N), 4);
 	}
 
 	#[test]
 	fn two_provided_two_tight()
 	{
-		assert_eq!(two(PROVIDED_TWO_TIGHT), 4);
+		assert_eq!(Solution::two(PROVIDED_TWO_TIGHT), 4);
 	}
 
 	#[test]
 	fn two_provided_two_full()
 	{
-		assert_eq!(two(PROVIDED_TWO), 10);
+		assert_eq!(Solution::two(PROVIDED_TWO), 10);
 	}
 }
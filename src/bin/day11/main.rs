@@ -1,15 +1,82 @@
 /**/
 
-use aoc2023::run;
+use aoc2023::parse::{self, ParseError};
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 use bitvec::array::BitArray;
 use smallvec::SmallVec;
 
-const INPUT: &str = include_str!("input.txt");
-
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
+}
+
+pub struct Solution;
+
+impl Solver for Solution
+{
+	type Parsed<'a> = Image;
+	type Answer = i64;
+
+	fn parse(input: &str) -> Result<Image, ParseError>
+	{
+		let mut image = Image {
+			galaxies: SmallVec::new(),
+			is_row_inhabited: BitArray::default(),
+			is_col_inhabited: BitArray::default(),
+			max_row: 0,
+			max_col: 0,
+		};
+		let max_len = image.is_row_inhabited.len();
+		for (r, line) in parse::lines(input).enumerate()
+		{
+			if r >= max_len || line.len() > max_len
+			{
+				return Err(ParseError::at(
+					input,
+					line,
+					format!(
+						"images larger than {max_len}x{max_len} are not \
+						 supported"
+					),
+				));
+			}
+			for (c, x) in line.as_bytes().iter().enumerate()
+			{
+				if *x != b'#' && *x != b'.'
+				{
+					return Err(ParseError::at(
+						input,
+						&line[c..],
+						"expected '#' or '.'",
+					));
+				}
+				if *x == b'#'
+				{
+					image.galaxies.push(Galaxy {
+						row: r as i64,
+						col: c as i64,
+					});
+					image.is_row_inhabited.set(r, true);
+					image.is_col_inhabited.set(c, true);
+					image.max_row = image.max_row.max(r);
+					image.max_col = image.max_col.max(c);
+				}
+			}
+		}
+		Ok(image)
+	}
+
+	fn part_one(image: &Image) -> i64
+	{
+		solve(image, 2)
+	}
+
+	fn part_two(image: &Image) -> i64
+	{
+		solve(image, 1_000_000)
+	}
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -19,35 +86,23 @@ struct Galaxy
 	col: i64,
 }
 
-fn one(input: &str) -> i64
+#[derive(Debug)]
+pub struct Image
 {
-	solve(input, 2)
+	galaxies: SmallVec<[Galaxy; 1024]>,
+	is_row_inhabited: BitArray<[u64; 3]>,
+	is_col_inhabited: BitArray<[u64; 3]>,
+	max_row: usize,
+	max_col: usize,
 }
 
-fn solve(input: &str, multiplier: i64) -> i64
+fn solve(image: &Image, multiplier: i64) -> i64
 {
-	let mut galaxies: SmallVec<[Galaxy; 1024]> = SmallVec::new();
-	let mut is_row_inhabited: BitArray<[u64; 3]> = BitArray::default();
-	let mut is_col_inhabited: BitArray<[u64; 3]> = BitArray::default();
-	let mut max_row = 0;
-	let mut max_col = 0;
-	for (r, line) in input.lines().filter(|x| !x.is_empty()).enumerate()
-	{
-		for (c, x) in line.as_bytes().iter().enumerate()
-		{
-			if *x == b'#'
-			{
-				galaxies.push(Galaxy {
-					row: r as i64,
-					col: c as i64,
-				});
-				is_row_inhabited.set(r, true);
-				is_col_inhabited.set(c, true);
-				max_row = max_row.max(r);
-				max_col = max_col.max(c);
-			}
-		}
-	}
+	let mut galaxies = image.galaxies.clone();
+	let is_row_inhabited = &image.is_row_inhabited;
+	let is_col_inhabited = &image.is_col_inhabited;
+	let max_row = image.max_row;
+	let max_col = image.max_col;
 
 	let addifier = multiplier - 1;
 	for r in (0..max_row).rev()
@@ -94,11 +149,6 @@ fn solve(input: &str, multiplier: i64) -> i64
 	sum_of_distances
 }
 
-fn two(input: &str) -> i64
-{
-	solve(input, 1_000_000)
-}
-
 #[cfg(test)]
 mod tests
 {
@@ -110,6 +160,6 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 374);
+		assert_eq!(Solution::one(PROVIDED), 374);
 	}
 }
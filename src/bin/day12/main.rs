@@ -1,30 +1,101 @@
 /**/
 
-use aoc2023::run;
+use aoc2023::parse::{self, ParseError};
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 use smallvec::SmallVec;
 
-const INPUT: &str = include_str!("input.txt");
-
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
+}
+
+pub struct Solution;
+
+impl Solver for Solution
+{
+	type Parsed<'a> = Vec<Record<'a>>;
+	type Answer = usize;
+
+	fn parse(input: &str) -> Result<Vec<Record<'_>>, ParseError>
+	{
+		parse::lines(input)
+			.map(|line| parse_record(input, line))
+			.collect()
+	}
+
+	fn part_one(records: &Vec<Record>) -> usize
+	{
+		records
+			.iter()
+			.map(|record| solve(record.symbols, &record.numbers))
+			.sum()
+	}
+
+	fn part_two(records: &Vec<Record>) -> usize
+	{
+		records
+			.iter()
+			.map(|record| {
+				let mut symbols = record.symbols.to_vec();
+				let mut numbers: SmallVec<[usize; 64]> = SmallVec::new();
+				numbers.extend_from_slice(&record.numbers);
+				for _ in 0..4
+				{
+					symbols.push(b'?');
+					symbols.extend_from_slice(record.symbols);
+					numbers.extend_from_slice(&record.numbers);
+				}
+				solve(&symbols, &numbers)
+			})
+			.sum()
+	}
 }
 
-fn one(input: &str) -> usize
+#[derive(Debug)]
+pub struct Record<'a>
 {
-	input.lines().filter(|x| !x.is_empty()).map(solve).sum()
+	symbols: &'a [u8],
+	numbers: SmallVec<[usize; 10]>,
 }
 
-fn solve(line: &str) -> usize
+fn parse_record<'a>(
+	input: &str,
+	line: &'a str,
+) -> Result<Record<'a>, ParseError>
 {
-	let (symbols, numbers) = line.split_once(' ').unwrap();
-	let numbers: SmallVec<[usize; 10]> = numbers
+	let (symbols, numbers) = line.split_once(' ').ok_or_else(|| {
+		ParseError::after(input, line, "expected '<springs> <group sizes>'")
+	})?;
+	if let Some(i) = symbols.find(|x| !"?#.".contains(x))
+	{
+		return Err(ParseError::at(
+			input,
+			&symbols[i..],
+			"expected '?', '#' or '.'",
+		));
+	}
+	let numbers = numbers
 		.split(',')
-		.map(|word| word.parse().unwrap())
-		.collect();
-	let symbols = symbols.as_bytes();
+		.map(|word| match parse::parse_word(input, word, "a group size")?
+		{
+			number @ 1..=31 => Ok(number),
+			_ => Err(ParseError::at(
+				input,
+				word,
+				"expected a group size between 1 and 31",
+			)),
+		})
+		.collect::<Result<_, _>>()?;
+	Ok(Record {
+		symbols: symbols.as_bytes(),
+		numbers,
+	})
+}
 
+fn solve(symbols: &[u8], numbers: &[usize]) -> usize
+{
 	let mut stack: SmallVec<[Probe; 128]> = SmallVec::new();
 	stack.push(Probe::default());
 
@@ -144,32 +215,6 @@ struct Probe
 	historic_bits: u64,
 }
 
-fn two(input: &str) -> usize
-{
-	input
-		.lines()
-		.filter(|x| !x.is_empty())
-		.map(|x| {
-			let (symbols, numbers) = x.split_once(' ').unwrap();
-			let mut x = symbols.to_string();
-			for _ in 0..4
-			{
-				x.push('?');
-				x.push_str(symbols);
-			}
-			x.push(' ');
-			x.push_str(numbers);
-			for _ in 0..4
-			{
-				x.push(',');
-				x.push_str(numbers);
-			}
-			// dbg!(&x);
-			solve(&x)
-		})
-		.sum()
-}
-
 #[cfg(test)]
 mod tests
 {
@@ -181,18 +226,18 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 21);
+		assert_eq!(Solution::one(PROVIDED), 21);
 	}
 
 	#[test]
 	fn one_edge_case_end()
 	{
-		assert_eq!(solve("#..??## 1,4"), 1);
+		assert_eq!(solve(b"#..??##", &[1, 4]), 1);
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 525152);
+		assert_eq!(Solution::two(PROVIDED), 525152);
 	}
 }
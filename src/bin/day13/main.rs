@@ -1,78 +1,119 @@
 /**/
 
-use aoc2023::run;
+use aoc2023::parse::ParseError;
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 use smallvec::SmallVec;
 
-const INPUT: &str = include_str!("input.txt");
+const MAX_LEN: usize = u32::BITS as usize;
 
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> usize
-{
-	let mirror_maker = || PerfectMirror { is_valid: true };
-	solve(input, mirror_maker)
-}
+pub struct Solution;
 
-fn solve<F, M>(input: &str, mirror_maker: F) -> usize
-where
-	F: Fn() -> M,
-	M: Mirror,
+impl Solver for Solution
 {
-	let lines = input.lines().chain(std::iter::once(""));
-	let mut rows: SmallVec<[u32; 32]> = SmallVec::new();
-	let mut cols: SmallVec<[u32; 32]> = SmallVec::new();
-	let mut answer = 0;
-	for line in lines
+	type Parsed<'a> = Vec<Pattern>;
+	type Answer = usize;
+
+	fn parse(input: &str) -> Result<Vec<Pattern>, ParseError>
 	{
-		if line.is_empty()
-		{
-			// dbg!();
-			// for row in &rows
-			// {
-			// 	dbg!(format!("{row:032b}"));
-			// }
-			// dbg!();
-			// for col in &cols
-			// {
-			// 	dbg!(format!("{col:032b}"));
-			// }
-
-			let value = solve_grid(&rows, &cols, &mirror_maker);
-			// dbg!(value);
-			answer += value;
-
-			rows.clear();
-			cols.clear();
-		}
-		else
+		let lines = input.lines().chain(std::iter::once(""));
+		let mut patterns = Vec::new();
+		let mut rows: SmallVec<[u32; 32]> = SmallVec::new();
+		let mut cols: SmallVec<[u32; 32]> = SmallVec::new();
+		for line in lines
 		{
-			let line = line.as_bytes();
-			if cols.is_empty()
+			if line.is_empty()
 			{
-				cols.resize(line.len(), 0);
+				if !rows.is_empty()
+				{
+					patterns.push(Pattern {
+						rows: std::mem::take(&mut rows),
+						cols: std::mem::take(&mut cols),
+					});
+				}
 			}
-			let mut row = 0;
-			for i in 0..cols.len()
+			else
 			{
-				let bit = match line[i]
-				{
-					b'#' => 1,
-					b'.' => 0,
-					_ => unreachable!(),
+				let error = |offset, message: &str| {
+					Err(ParseError::at(input, &line[offset..], message))
 				};
-				row <<= 1;
-				row |= bit;
-				cols[i] <<= 1;
-				cols[i] |= bit;
+				if cols.is_empty()
+				{
+					cols.resize(line.len(), 0);
+				}
+				if line.len() != cols.len()
+				{
+					return error(
+						0,
+						"expected as many columns as the first row",
+					);
+				}
+				if line.len() > MAX_LEN || rows.len() == MAX_LEN
+				{
+					return error(
+						0,
+						&format!(
+							"patterns larger than {MAX_LEN}x{MAX_LEN} are not \
+							 supported"
+						),
+					);
+				}
+				let line = line.as_bytes();
+				let mut row = 0;
+				for i in 0..cols.len()
+				{
+					let bit = match line[i]
+					{
+						b'#' => 1,
+						b'.' => 0,
+						_ => return error(i, "expected '#' or '.'"),
+					};
+					row <<= 1;
+					row |= bit;
+					cols[i] <<= 1;
+					cols[i] |= bit;
+				}
+				rows.push(row);
 			}
-			rows.push(row);
 		}
+		Ok(patterns)
+	}
+
+	fn part_one(patterns: &Vec<Pattern>) -> usize
+	{
+		let mirror_maker = || PerfectMirror { is_valid: true };
+		solve(patterns, mirror_maker)
+	}
+
+	fn part_two(patterns: &Vec<Pattern>) -> usize
+	{
+		let mirror_maker = || SmudgedMirror { num_smudges: 0 };
+		solve(patterns, mirror_maker)
 	}
-	answer
+}
+
+#[derive(Debug)]
+pub struct Pattern
+{
+	rows: SmallVec<[u32; 32]>,
+	cols: SmallVec<[u32; 32]>,
+}
+
+fn solve<F, M>(patterns: &[Pattern], mirror_maker: F) -> usize
+where
+	F: Fn() -> M,
+	M: Mirror,
+{
+	patterns
+		.iter()
+		.map(|pattern| solve_grid(&pattern.rows, &pattern.cols, &mirror_maker))
+		.sum()
 }
 
 trait Mirror
@@ -176,12 +217,6 @@ where
 	panic!("No symmetry detected");
 }
 
-fn two(input: &str) -> usize
-{
-	let mirror_maker = || SmudgedMirror { num_smudges: 0 };
-	solve(input, mirror_maker)
-}
-
 #[cfg(test)]
 mod tests
 {
@@ -193,12 +228,12 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 405);
+		assert_eq!(Solution::one(PROVIDED), 405);
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 400);
+		assert_eq!(Solution::two(PROVIDED), 400);
 	}
 }
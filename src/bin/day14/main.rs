@@ -1,300 +1,143 @@
 /**/
 
-use aoc2023::run;
-use smallvec::SmallVec;
-
-const INPUT: &str = include_str!("input.txt");
+use aoc2023::bit_grid::BitGrid;
+use aoc2023::cycle::Cycle;
+use aoc2023::geometry::Direction;
+use aoc2023::parse::{self, ParseError};
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> usize
-{
-	let mut roll_point = [0u8; 128];
-	let mut num_boulders_placed = 0;
-	let mut total_load = 0;
-	for (r, line) in input.lines().filter(|x| !x.is_empty()).enumerate()
-	{
-		for (c, x) in line.as_bytes().iter().enumerate()
-		{
-			match *x
-			{
-				b'#' => roll_point[c] = (r + 1) as u8,
-				b'O' =>
-				{
-					num_boulders_placed += 1;
-					if roll_point[c] < r as u8
-					{
-						total_load += r - roll_point[c] as usize;
-						roll_point[c] += 1;
-					}
-					else
-					{
-						roll_point[c] = (r + 1) as u8;
-					}
-				}
-				b'.' => (),
-				_ => unreachable!(),
-			}
-		}
-		total_load += num_boulders_placed;
-	}
-	total_load
-}
+pub struct Solution;
 
-#[derive(Debug)]
-struct Grid
+impl Solver for Solution
 {
-	data: [u128; 128],
-}
-
-impl Grid
-{
-	fn empty() -> Grid
-	{
-		Grid { data: [0u128; 128] }
-	}
-
-	fn set(&mut self, r: usize, c: usize)
-	{
-		self.data[r] |= 1 << c;
-	}
-
-	fn clear(&mut self, r: usize, c: usize)
-	{
-		self.data[r] &= !(1 << c);
-	}
-
-	fn get(&self, r: usize, c: usize) -> bool
-	{
-		(self.data[r] & (1 << c)) != 0
-	}
-
-	fn roll_north(&mut self, pillars: &Grid, num_rows: usize, num_cols: usize)
-	{
-		let mut roll_point = [0usize; 128];
-		for r in 0..num_rows
+	type Parsed<'a> = Platform;
+	type Answer = u32;
+
+	fn parse(input: &str) -> Result<Platform, ParseError>
+	{
+		let lines: Vec<&str> = parse::lines(input).collect();
+		let num_rows = lines.len();
+		let num_cols = lines.first().map_or(0, |line| line.len());
+		let mut platform = Platform {
+			pillars: BitGrid::new(num_cols, num_rows),
+			boulders: BitGrid::new(num_cols, num_rows),
+		};
+		for (r, line) in lines.into_iter().enumerate()
 		{
-			for c in 0..num_cols
+			if line.len() != num_cols
 			{
-				if pillars.get(r, c)
-				{
-					roll_point[c] = r + 1;
-				}
-				else if self.get(r, c)
-				{
-					if roll_point[c] < r
-					{
-						self.clear(r, c);
-						self.set(roll_point[c], c);
-						roll_point[c] += 1;
-					}
-					else
-					{
-						roll_point[c] = r + 1;
-					}
-				}
+				return Err(ParseError::at(
+					input,
+					line,
+					"expected as many columns as the first row",
+				));
 			}
-		}
-	}
-
-	fn roll_south(&mut self, pillars: &Grid, num_rows: usize, num_cols: usize)
-	{
-		let mut roll_point = [num_rows - 1; 128];
-		for r in (0..num_rows).rev()
-		{
-			for c in 0..num_cols
+			for (c, x) in line.as_bytes().iter().enumerate()
 			{
-				if pillars.get(r, c)
-				{
-					roll_point[c] = r.saturating_sub(1);
-				}
-				else if self.get(r, c)
+				match *x
 				{
-					if roll_point[c] > r
-					{
-						self.clear(r, c);
-						self.set(roll_point[c], c);
-						roll_point[c] -= 1;
-					}
-					else
+					b'#' => platform.pillars.set(r, c),
+					b'O' => platform.boulders.set(r, c),
+					b'.' => (),
+					_ =>
 					{
-						roll_point[c] = r.saturating_sub(1)
+						return Err(ParseError::at(
+							input,
+							&line[c..],
+							"expected '#', 'O' or '.'",
+						))
 					}
 				}
 			}
 		}
+		Ok(platform)
 	}
 
-	fn roll_west(&mut self, pillars: &Grid, num_rows: usize, num_cols: usize)
+	fn part_one(platform: &Platform) -> u32
 	{
-		let mut roll_point = [0usize; 128];
-		for c in 0..num_cols
-		{
-			for r in 0..num_rows
-			{
-				if pillars.get(r, c)
-				{
-					roll_point[r] = c + 1;
-				}
-				else if self.get(r, c)
-				{
-					if roll_point[r] < c
-					{
-						self.clear(r, c);
-						self.set(r, roll_point[r]);
-						roll_point[r] += 1;
-					}
-					else
-					{
-						roll_point[r] = c + 1;
-					}
-				}
-			}
-		}
+		let mut boulders = platform.boulders.clone();
+		platform.roll(&mut boulders, 0);
+		load_on_north_pillar(&boulders)
 	}
 
-	fn roll_east(&mut self, pillars: &Grid, num_rows: usize, num_cols: usize)
+	fn part_two(platform: &Platform) -> u32
 	{
-		let mut roll_point = [num_cols - 1; 128];
-		for c in (0..num_cols).rev()
-		{
-			for r in 0..num_rows
-			{
-				if pillars.get(r, c)
-				{
-					roll_point[r] = c.saturating_sub(1);
-				}
-				else if self.get(r, c)
-				{
-					if roll_point[r] > c
-					{
-						self.clear(r, c);
-						self.set(r, roll_point[r]);
-						roll_point[r] -= 1;
-					}
-					else
-					{
-						roll_point[r] = c.saturating_sub(1)
-					}
-				}
-			}
-		}
-	}
-
-	fn load_on_north_pillar(&self, num_rows: usize) -> u32
-	{
-		let mut num_boulders = 0;
-		let mut total_load = 0;
-		for row in &self.data[0..num_rows]
-		{
-			num_boulders += row.count_ones();
-			total_load += num_boulders;
-		}
-		total_load
+		run_simulation(platform, NUM_ITERATIONS)
 	}
 }
 
-const NUM_CYCLES: usize = 1000000000;
-const NUM_ITERATIONS: usize = 4 * NUM_CYCLES;
-
-fn two(input: &str) -> u32
+#[derive(Debug)]
+pub struct Platform
 {
-	run_simulation(input, NUM_ITERATIONS)
+	pillars: BitGrid,
+	boulders: BitGrid,
 }
 
-fn run_simulation(input: &str, num_iterations: usize) -> u32
+const ROLL_DIRECTIONS: [Direction; 4] = [
+	Direction::Up,
+	Direction::Left,
+	Direction::Down,
+	Direction::Right,
+];
+
+impl Platform
 {
-	let mut pillar_grid = Grid::empty();
-	let mut boulder_grid = Grid::empty();
-	let mut num_rows = 0;
-	let mut num_cols = 0;
-	for (r, line) in input.lines().filter(|x| !x.is_empty()).enumerate()
+	/// Rolls the boulders for the `t`-th time, which is in turn to the
+	/// north, west, south and east. All boulders with room in front of them
+	/// move a step at once, until none of them can.
+	fn roll(&self, boulders: &mut BitGrid, t: usize)
 	{
-		for (c, x) in line.as_bytes().iter().enumerate()
+		let direction = ROLL_DIRECTIONS[t % 4];
+		loop
 		{
-			match *x
+			let mut moved = boulders.shifted(direction);
+			moved.and_not(&self.pillars);
+			moved.and_not(boulders);
+			if moved.is_empty()
 			{
-				b'#' => pillar_grid.set(r, c),
-				b'O' => boulder_grid.set(r, c),
-				b'.' => (),
-				_ => unreachable!(),
+				break;
 			}
+			boulders.and_not(&moved.shifted(direction.reverse()));
+			boulders.or(&moved);
 		}
-		num_rows = r + 1;
-		num_cols = line.as_bytes().len();
 	}
 
-	let mut historic_loads: SmallVec<[u32; 1024]> = SmallVec::new();
-	for t in 0..num_iterations
+	fn spin(&self, boulders: &BitGrid) -> BitGrid
 	{
-		match t % 4
-		{
-			0 => boulder_grid.roll_north(&pillar_grid, num_rows, num_cols),
-			1 => boulder_grid.roll_west(&pillar_grid, num_rows, num_cols),
-			2 => boulder_grid.roll_south(&pillar_grid, num_rows, num_cols),
-			3 => boulder_grid.roll_east(&pillar_grid, num_rows, num_cols),
-			_ => unreachable!(),
-		}
+		let mut boulders = boulders.clone();
+		(0..4).for_each(|t| self.roll(&mut boulders, t));
+		boulders
+	}
+}
 
-		if cfg!(test)
-		{
-			for r in 0..num_rows
-			{
-				for c in 0..num_cols
-				{
-					if pillar_grid.get(r, c)
-					{
-						print!("#");
-					}
-					else if boulder_grid.get(r, c)
-					{
-						print!("O");
-					}
-					else
-					{
-						print!(".");
-					}
-				}
-				println!();
-			}
-		}
+fn load_on_north_pillar(boulders: &BitGrid) -> u32
+{
+	let num_rows = boulders.height();
+	(0..num_rows)
+		.map(|r| (num_rows - r) * boulders.count_ones_in_row(r))
+		.sum::<usize>() as u32
+}
 
-		if t % 4 == 3
-		{
-			dbg!(t);
-			let load = boulder_grid.load_on_north_pillar(num_rows);
-			dbg!(load);
-			historic_loads.push(load);
+const NUM_CYCLES: usize = 1000000000;
+const NUM_ITERATIONS: usize = 4 * NUM_CYCLES;
 
-			if t > 400
-			{
-				for k in 1..80
-				{
-					if (num_iterations - (t + 1)) % (4 * k) != 0
-					{
-						continue;
-					}
-					let i = historic_loads.len() - 1;
-					if (1..5)
-						.map(|j| historic_loads[i - k * j])
-						.all(|x| x == load)
-					{
-						dbg!(k);
-						return load;
-					}
-				}
-			}
-		}
-	}
-	match historic_loads.last()
-	{
-		Some(final_load) => *final_load,
-		None => boulder_grid.load_on_north_pillar(num_rows),
-	}
+/// The load after rolling the boulders `num_iterations` times. The boulders
+/// soon settle into a loop, which we find by remembering every arrangement
+/// after whole spin cycles.
+fn run_simulation(platform: &Platform, num_iterations: usize) -> u32
+{
+	let history =
+		Cycle::with_hash_map(platform.boulders.clone(), |x| platform.spin(x));
+	let mut boulders = history.state_at(num_iterations / 4).clone();
+	(0..num_iterations % 4).for_each(|t| platform.roll(&mut boulders, t));
+	load_on_north_pillar(&boulders)
 }
 
 #[cfg(test)]
@@ -308,38 +151,75 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 136);
+		assert_eq!(Solution::one(PROVIDED), 136);
 	}
 
 	#[test]
 	fn one_simulation_provided()
 	{
-		assert_eq!(run_simulation(PROVIDED, 1), 136);
+		assert_eq!(run_simulation(&Solution::parse(PROVIDED).unwrap(), 1), 136);
 	}
 
 	#[test]
 	fn one_simulation_12_provided()
 	{
-		run_simulation(PROVIDED, 12);
+		run_simulation(&Solution::parse(PROVIDED).unwrap(), 12);
 	}
 
 	#[test]
 	fn test_boulder_drop()
 	{
-		assert_eq!(one(".\n.\n.\nO"), 4);
-		assert_eq!(one(".\n#\n.\nO"), 2);
-		assert_eq!(one(".\n.\nO\nO"), 7);
+		assert_eq!(Solution::one(".\n.\n.\nO"), 4);
+		assert_eq!(Solution::one(".\n#\n.\nO"), 2);
+		assert_eq!(Solution::one(".\n.\nO\nO"), 7);
+	}
+
+	#[test]
+	fn one_wide()
+	{
+		let input = format!("{}\n{}O\n", ".".repeat(200), "#".repeat(199));
+		assert_eq!(Solution::one(&input), 2);
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 64);
+		assert_eq!(Solution::two(PROVIDED), 64);
 	}
 
 	#[test]
 	fn two_simulation_provided()
 	{
-		run_simulation(PROVIDED, 1000);
+		run_simulation(&Solution::parse(PROVIDED).unwrap(), 1000);
+	}
+
+	#[test]
+	fn two_simulation_agrees()
+	{
+		let platform = Solution::parse(PROVIDED).unwrap();
+		let mut boulders = platform.boulders.clone();
+		for t in 0..200
+		{
+			let load = load_on_north_pillar(&boulders);
+			assert_eq!(run_simulation(&platform, t), load, "{t} iterations");
+			platform.roll(&mut boulders, t);
+		}
+	}
+
+	#[test]
+	fn two_brent_agrees()
+	{
+		let platform = Solution::parse(PROVIDED).unwrap();
+		let spin = |x: &BitGrid| platform.spin(x);
+		let cycle = Cycle::brent(&platform.boulders, spin);
+		let history = Cycle::with_hash_map(platform.boulders.clone(), spin);
+		assert_eq!(history.cycle, cycle);
+		assert_eq!(
+			cycle,
+			Cycle {
+				tail_len: 3,
+				period: 7,
+			}
+		);
 	}
 }
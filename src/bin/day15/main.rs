@@ -1,22 +1,71 @@
 /**/
 
-use aoc2023::run;
+use aoc2023::parse::ParseError;
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 use parse_display::FromStr;
 use smallvec::SmallVec;
 use std::str::FromStr;
 
-const INPUT: &str = include_str!("input.txt");
-
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> u32
+pub struct Solution;
+
+impl Solver for Solution
 {
-	let input = input.lines().next().unwrap();
-	input.split(',').map(hash_word).map(u32::from).sum()
+	type Parsed<'a> = Vec<Step<'a>>;
+	type Answer = u32;
+
+	fn parse(input: &str) -> Result<Vec<Step<'_>>, ParseError>
+	{
+		let line = input.lines().next().unwrap_or_default();
+		line.split(',')
+			.map(|text| {
+				let instruction =
+					Instruction::from_str(text).map_err(|_| {
+						ParseError::at(
+							input,
+							text,
+							format!(
+								"expected 'label=N' or 'label-', found \
+								 '{text}'"
+							),
+						)
+					})?;
+				Ok(Step { text, instruction })
+			})
+			.collect()
+	}
+
+	fn part_one(steps: &Vec<Step>) -> u32
+	{
+		steps
+			.iter()
+			.map(|step| hash_word(step.text))
+			.map(u32::from)
+			.sum()
+	}
+
+	fn part_two(steps: &Vec<Step>) -> u32
+	{
+		let mut hashmap = HashMap::new();
+		for step in steps
+		{
+			match &step.instruction
+			{
+				Instruction::Insert { word, focal_length } =>
+				{
+					hashmap.insert(word, *focal_length);
+				}
+				Instruction::Delete { word } => hashmap.delete(word),
+			}
+		}
+		hashmap.total_focusing_power()
+	}
 }
 
 fn hash_word(word: &str) -> u8
@@ -35,6 +84,13 @@ fn safe_hash_word(word: &str) -> u32
 	u32::from_le_bytes(bytes)
 }
 
+#[derive(Debug)]
+pub struct Step<'a>
+{
+	text: &'a str,
+	instruction: Instruction,
+}
+
 #[derive(Debug, FromStr)]
 enum Instruction
 {
@@ -117,25 +173,6 @@ impl HashMap
 	}
 }
 
-fn two(input: &str) -> u32
-{
-	let input = input.lines().next().unwrap();
-	let mut hashmap = HashMap::new();
-	for instruction in input.split(',')
-	{
-		let instruction = Instruction::from_str(instruction).unwrap();
-		match instruction
-		{
-			Instruction::Insert { word, focal_length } =>
-			{
-				hashmap.insert(&word, focal_length);
-			}
-			Instruction::Delete { word } => hashmap.delete(&word),
-		}
-	}
-	hashmap.total_focusing_power()
-}
-
 #[cfg(test)]
 mod tests
 {
@@ -147,12 +184,12 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 1320);
+		assert_eq!(Solution::one(PROVIDED), 1320);
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 145);
+		assert_eq!(Solution::two(PROVIDED), 145);
 	}
 }
@@ -1,107 +1,164 @@
 /**/
 
-use aoc2023::run;
+use aoc2023::bit_grid::BitGrid;
+use aoc2023::geometry::Direction;
+use aoc2023::grid::Grid;
+use aoc2023::parse::ParseError;
+use aoc2023::runner;
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 use smallvec::SmallVec;
 
-const INPUT: &str = include_str!("input.txt");
-
-const GRID_SIZE: usize = 128;
-
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> usize
+pub struct Solution;
+
+impl Solver for Solution
 {
-	let mut grid = [[0; GRID_SIZE]; GRID_SIZE];
-	let (num_rows, num_cols) = parse_grid(&mut grid, input);
+	type Parsed<'a> = Grid<u8>;
+	type Answer = usize;
 
-	let head = Head {
-		row: 0,
-		col: 0,
-		direction: EAST,
-	};
-	energize_grid(&mut grid, num_rows, num_cols, head)
+	fn parse(input: &str) -> Result<Grid<u8>, ParseError>
+	{
+		parse_grid(input)
+	}
+
+	fn part_one(grid: &Grid<u8>) -> usize
+	{
+		let entry = Entry {
+			row: 0,
+			col: 0,
+			direction: Direction::Right,
+		};
+		trace_beams(grid, entry).num_energized()
+	}
+
+	fn part_two(grid: &Grid<u8>) -> usize
+	{
+		find_best_entry(grid, runner::num_threads())
+			.map_or(0, |best| best.num_energized)
+	}
 }
 
-fn parse_grid(grid: &mut [[u8; 128]; 128], input: &str) -> (usize, usize)
+/// The contraption is written as a grid of symbols, one row per line:
+///
+/// - `.` is empty space, which beams pass straight through;
+/// - `/` and `\` are mirrors, which turn beams by a right angle;
+/// - `-` and `|` are splitters, which split beams that hit their flat side
+///   in two, and let beams along them pass;
+/// - `#` is a wall, which absorbs beams;
+/// - `^`, `>`, `v` and `<` are one-way prisms, which send beams on in the
+///   direction they point, except beams coming head-on, which they absorb.
+///
+/// A cell is only energized when a beam leaves it, so walls never are.
+fn parse_grid(input: &str) -> Result<Grid<u8>, ParseError>
 {
-	let lines = input.lines().filter(|x| !x.is_empty()).enumerate();
-	let mut num_rows = 0;
-	let mut num_cols = 0;
-	for (r, line) in lines
+	let grid = Grid::try_parse(
+		input,
+		"'.', a mirror ('/', '\\'), a splitter ('-', '|'), a wall ('#') or a \
+		 prism ('^', '>', 'v', '<')",
+		component_index,
+	)?;
+	if grid.width() == 0
 	{
-		num_rows = num_rows.max(r + 1);
-		num_cols = line.as_bytes().len();
-		for (c, symbol) in line.as_bytes().iter().enumerate()
-		{
-			grid[r][c] = Cell::parse(*symbol);
-		}
+		return Err(ParseError::new(1, 1, "expected a contraption"));
 	}
+	Ok(grid)
+}
+
+/// Where a beam enters the contraption, and the way it is heading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry
+{
+	pub row: usize,
+	pub col: usize,
+	pub direction: Direction,
+}
+
+/// What the beams lit up: the energized cells, and for every cell the
+/// directions in which beams left it.
+#[derive(Debug, Clone)]
+pub struct Trace
+{
+	pub energized: BitGrid,
+	headings: Grid<u8>,
+}
 
-	if cfg!(debug_assertions)
+impl Trace
+{
+	pub fn num_energized(&self) -> usize
 	{
-		print_contraptions(&grid, num_rows, num_cols);
+		self.energized.count_ones()
 	}
 
-	(num_rows, num_cols)
+	pub fn headings(
+		&self,
+		row: usize,
+		col: usize,
+	) -> impl Iterator<Item = Direction> + '_
+	{
+		Direction::ALL
+			.into_iter()
+			.filter(move |&x| self.headings[row][col] & direction_bit(x) != 0)
+	}
 }
 
-fn energize_grid(
-	grid: &mut [[u8; 128]; 128],
-	num_rows: usize,
-	num_cols: usize,
-	head: Head,
-) -> usize
+/// Follows a beam, and all the beams it splits into, from `entry` until
+/// each of them has left the contraption or gone around in a loop.
+pub fn trace_beams(grid: &Grid<u8>, entry: Entry) -> Trace
 {
+	let num_rows = grid.height();
+	let num_cols = grid.width();
+	let mut headings = Grid::new(num_cols, num_rows, 0u8);
 	let mut stack: SmallVec<[Head; 128]> = SmallVec::new();
-	stack.push(head);
+	stack.push(Head {
+		row: entry.row,
+		col: entry.col,
+		direction: direction_bit(entry.direction),
+	});
 
 	'withstack: while let Some(head) = stack.pop()
 	{
 		let mut head: Head = head;
 		while is_empty(grid[head.row][head.col])
 		{
-			grid[head.row][head.col] |= head.direction;
-			match head.direction
+			headings[head.row][head.col] |= head.direction;
+			match head.step(num_rows, num_cols)
 			{
-				NORTH if head.row > 0 => head.row -= 1,
-				SOUTH if head.row + 1 < num_rows => head.row += 1,
-				WEST if head.col > 0 => head.col -= 1,
-				EAST if head.col + 1 < num_cols => head.col += 1,
-				_ => continue 'withstack,
+				Some(next) => head = next,
+				None => continue 'withstack,
 			}
 		}
 
-		let cell = &mut grid[head.row][head.col];
-		for direction in transmit(*cell, head.direction)
+		let heading = &mut headings[head.row][head.col];
+		let outgoing = transmit(grid[head.row][head.col], head.direction);
+		for direction in [NORTH, EAST, SOUTH, WEST]
 		{
-			if *cell & direction == 0
+			if outgoing & direction != 0 && *heading & direction == 0
 			{
-				*cell |= direction;
-				let mut new_head = Head { direction, ..head };
-				match direction
+				*heading |= direction;
+				let turned = Head { direction, ..head };
+				if let Some(next) = turned.step(num_rows, num_cols)
 				{
-					NORTH if new_head.row > 0 => new_head.row -= 1,
-					SOUTH if new_head.row + 1 < num_rows => new_head.row += 1,
-					WEST if new_head.col > 0 => new_head.col -= 1,
-					EAST if new_head.col + 1 < num_cols => new_head.col += 1,
-					_ => continue,
+					stack.push(next);
 				}
-				stack.push(new_head);
 			}
 		}
 	}
 
-	if cfg!(debug_assertions)
-	{
-		print_energized(&grid, num_rows, num_cols);
+	let energized =
+		BitGrid::from_fn(num_cols, num_rows, |r, c| headings[r][c] != 0);
+	Trace {
+		energized,
+		headings,
 	}
-	count_energized(&grid, num_rows, num_cols)
 }
 
+#[derive(Debug, Clone, Copy)]
 struct Head
 {
 	row: usize,
@@ -109,209 +166,182 @@ struct Head
 	direction: u8,
 }
 
-const MIRROR_BLTR: u8 = 0b0010;
-const MIRROR_TLBR: u8 = 0b0001;
-const SPLITTER_H: u8 = 0b0100;
-const SPLITTER_V: u8 = 0b1000;
-const CONTRAPTION_BITS: u8 =
-	MIRROR_BLTR | MIRROR_TLBR | SPLITTER_H | SPLITTER_V;
-
-const NORTH: u8 = 0b1000_0000;
-const SOUTH: u8 = 0b0100_0000;
-const WEST: u8 = 0b0010_0000;
-const EAST: u8 = 0b0001_0000;
-const ENERGIZED_BITS: u8 = NORTH | SOUTH | WEST | EAST;
-
-fn transmit(cell: u8, direction: u8) -> [u8; 2]
+impl Head
 {
-	match cell & CONTRAPTION_BITS
+	fn step(self, num_rows: usize, num_cols: usize) -> Option<Head>
 	{
-		MIRROR_BLTR => match direction
-		{
-			NORTH => [EAST, EAST],
-			SOUTH => [WEST, WEST],
-			WEST => [SOUTH, SOUTH],
-			EAST => [NORTH, NORTH],
-			_ => unreachable!(),
-		},
-		MIRROR_TLBR => match direction
-		{
-			NORTH => [WEST, WEST],
-			SOUTH => [EAST, EAST],
-			WEST => [NORTH, NORTH],
-			EAST => [SOUTH, SOUTH],
-			_ => unreachable!(),
-		},
-		SPLITTER_H => match direction
+		let mut next = self;
+		match self.direction
 		{
-			NORTH => [WEST, EAST],
-			SOUTH => [WEST, EAST],
-			WEST => [WEST, WEST],
-			EAST => [EAST, EAST],
-			_ => unreachable!(),
-		},
-		SPLITTER_V => match direction
-		{
-			NORTH => [NORTH, NORTH],
-			SOUTH => [SOUTH, SOUTH],
-			WEST => [NORTH, SOUTH],
-			EAST => [NORTH, SOUTH],
-			_ => unreachable!(),
-		},
-		_ => unreachable!(),
+			NORTH if self.row > 0 => next.row -= 1,
+			SOUTH if self.row + 1 < num_rows => next.row += 1,
+			WEST if self.col > 0 => next.col -= 1,
+			EAST if self.col + 1 < num_cols => next.col += 1,
+			_ => return None,
+		}
+		Some(next)
 	}
 }
 
-struct Cell;
+const NORTH: u8 = 1 << Direction::Up as u8;
+const EAST: u8 = 1 << Direction::Right as u8;
+const SOUTH: u8 = 1 << Direction::Down as u8;
+const WEST: u8 = 1 << Direction::Left as u8;
 
-impl Cell
+fn direction_bit(direction: Direction) -> u8
 {
-	fn parse(symbol: u8) -> u8
+	1 << direction as u8
+}
+
+/// A kind of cell: the symbol it is written as, and for beams heading up,
+/// right, down and left into it (the order of `Direction::ALL`), the
+/// directions in which beams leave it.
+struct Component
+{
+	symbol: char,
+	outgoing: [u8; 4],
+}
+
+/// Cells of the contraption are indices into this table.
+const COMPONENTS: [Component; 10] = [
+	Component {
+		symbol: '.',
+		outgoing: [NORTH, EAST, SOUTH, WEST],
+	},
+	Component {
+		symbol: '/',
+		outgoing: [EAST, NORTH, WEST, SOUTH],
+	},
+	Component {
+		symbol: '\\',
+		outgoing: [WEST, SOUTH, EAST, NORTH],
+	},
+	Component {
+		symbol: '-',
+		outgoing: [WEST | EAST, EAST, WEST | EAST, WEST],
+	},
+	Component {
+		symbol: '|',
+		outgoing: [NORTH, NORTH | SOUTH, SOUTH, NORTH | SOUTH],
+	},
+	Component {
+		symbol: '#',
+		outgoing: [0, 0, 0, 0],
+	},
+	Component {
+		symbol: '^',
+		outgoing: [NORTH, NORTH, 0, NORTH],
+	},
+	Component {
+		symbol: '>',
+		outgoing: [EAST, EAST, EAST, 0],
+	},
+	Component {
+		symbol: 'v',
+		outgoing: [0, SOUTH, SOUTH, SOUTH],
+	},
+	Component {
+		symbol: '<',
+		outgoing: [WEST, 0, WEST, WEST],
+	},
+];
+/// The fast path in `trace_beams` skips over cells of this component.
+const EMPTY: u8 = const_component_index('.');
+
+const fn const_component_index(symbol: char) -> u8
+{
+	let mut i = 0;
+	while COMPONENTS[i].symbol != symbol
 	{
-		match symbol
-		{
-			b'.' => 0,
-			b'/' => MIRROR_BLTR,
-			b'\\' => MIRROR_TLBR,
-			b'-' => SPLITTER_H,
-			b'|' => SPLITTER_V,
-			_ => unreachable!(),
-		}
+		i += 1;
 	}
+	i as u8
 }
 
-fn is_empty(cell: u8) -> bool
+fn component_index(symbol: char) -> Option<u8>
 {
-	cell & CONTRAPTION_BITS == 0
+	let i = COMPONENTS.iter().position(|x| x.symbol == symbol)?;
+	Some(i as u8)
 }
 
-fn is_energized(cell: u8) -> bool
+/// The directions in which a beam heading in `direction` leaves `cell`.
+fn transmit(cell: u8, direction: u8) -> u8
 {
-	cell & ENERGIZED_BITS != 0
+	COMPONENTS[cell as usize].outgoing[direction.trailing_zeros() as usize]
 }
 
-fn print_contraptions(
-	grid: &[[u8; GRID_SIZE]; GRID_SIZE],
-	num_rows: usize,
-	num_cols: usize,
-)
+fn is_empty(cell: u8) -> bool
 {
-	println!();
-	let mut buffer = String::new();
-	for row in &grid[0..num_rows]
-	{
-		buffer.clear();
-		for cell in &row[0..num_cols]
-		{
-			match cell & CONTRAPTION_BITS
-			{
-				0 => buffer.push('.'),
-				MIRROR_BLTR => buffer.push('/'),
-				MIRROR_TLBR => buffer.push('\\'),
-				SPLITTER_H => buffer.push('-'),
-				SPLITTER_V => buffer.push('|'),
-				_ => unreachable!(),
-			}
-		}
-		println!("{buffer}");
-	}
-	println!();
+	cell == EMPTY
 }
 
-fn print_energized(
-	grid: &[[u8; GRID_SIZE]; GRID_SIZE],
-	num_rows: usize,
-	num_cols: usize,
-)
+/// Every way into the contraption from its edges: along the top heading
+/// down, then the right side heading left, the bottom heading up and the
+/// left side heading right.
+fn edge_entries(num_rows: usize, num_cols: usize) -> Vec<Entry>
 {
-	println!();
-	let mut buffer = String::new();
-	for row in &grid[0..num_rows]
+	let entry = |row, col, direction| Entry {
+		row,
+		col,
+		direction,
+	};
+	if num_rows == 0 || num_cols == 0
 	{
-		buffer.clear();
-		for cell in &row[0..num_cols]
-		{
-			if is_energized(*cell)
-			{
-				buffer.push('#');
-			}
-			else
-			{
-				buffer.push('.');
-			}
-		}
-		println!("{buffer}");
+		return Vec::new();
 	}
-	println!();
+	let top = (0..num_cols).map(|c| entry(0, c, Direction::Down));
+	let right = (0..num_rows).map(|r| entry(r, num_cols - 1, Direction::Left));
+	let bottom = (0..num_cols).map(|c| entry(num_rows - 1, c, Direction::Up));
+	let left = (0..num_rows).map(|r| entry(r, 0, Direction::Right));
+	top.chain(right).chain(bottom).chain(left).collect()
 }
 
-fn count_energized(
-	grid: &[[u8; GRID_SIZE]; GRID_SIZE],
-	num_rows: usize,
-	num_cols: usize,
-) -> usize
+/// An entry that energizes as many cells as possible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BestEntry
 {
-	grid[0..num_rows]
-		.iter()
-		.map(|row| {
-			row[0..num_cols]
-				.iter()
-				.filter(|x| is_energized(**x))
-				.count()
-		})
-		.sum()
+	pub entry: Entry,
+	pub num_energized: usize,
 }
 
-fn clear_grid(grid: &mut [[u8; GRID_SIZE]; GRID_SIZE])
+/// Tries every entry from the edges, split over `num_threads` threads. Of
+/// entries that tie, the one that comes first in `edge_entries` wins.
+pub fn find_best_entry(grid: &Grid<u8>, num_threads: usize)
+	-> Option<BestEntry>
 {
-	for row in grid
-	{
-		for cell in row
+	let entries = edge_entries(grid.height(), grid.width());
+	let chunk_size = entries.len().div_ceil(num_threads.max(1)).max(1);
+	let better = |best: BestEntry, x: BestEntry| {
+		if x.num_energized > best.num_energized
 		{
-			*cell = *cell & CONTRAPTION_BITS;
+			x
 		}
-	}
-}
-
-fn two(input: &str) -> usize
-{
-	let mut grid = [[0; GRID_SIZE]; GRID_SIZE];
-	let (num_rows, num_cols) = parse_grid(&mut grid, input);
-
-	(0..std::cmp::max(num_rows, num_cols))
-		.flat_map(|i| {
-			[
-				Head {
-					row: i,
-					col: 0,
-					direction: EAST,
-				},
-				Head {
-					row: i,
-					col: num_cols - 1,
-					direction: WEST,
-				},
-				Head {
-					row: 0,
-					col: i,
-					direction: SOUTH,
-				},
-				Head {
-					row: num_rows - 1,
-					col: i,
-					direction: NORTH,
-				},
-			]
+		else
+		{
+			best
+		}
+	};
+	std::thread::scope(|scope| {
+		let workers: Vec<_> = entries
+			.chunks(chunk_size)
+			.map(|chunk| {
+				scope.spawn(move || {
+					chunk
+						.iter()
+						.map(|&entry| BestEntry {
+							entry,
+							num_energized: trace_beams(grid, entry)
+								.num_energized(),
+						})
+						.reduce(better)
+				})
+			})
+			.collect();
+		workers
 			.into_iter()
-		})
-		.filter(|head| head.row < num_rows && head.col < num_cols)
-		.map(|head| {
-			let result = energize_grid(&mut grid, num_rows, num_cols, head);
-			clear_grid(&mut grid);
-			result
-		})
-		.max()
-		.unwrap_or(0)
+			.filter_map(|worker| worker.join().unwrap())
+			.reduce(better)
+	})
 }
 
 #[cfg(test)]
@@ -325,27 +355,110 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 46);
+		assert_eq!(Solution::one(PROVIDED), 46);
 	}
 
 	#[test]
-	fn two_best_provided()
+	fn one_trace_provided()
 	{
-		let mut grid = [[0; GRID_SIZE]; GRID_SIZE];
-		let (num_rows, num_cols) = parse_grid(&mut grid, PROVIDED);
+		let grid = parse_grid(PROVIDED).unwrap();
+		let entry = Entry {
+			row: 0,
+			col: 0,
+			direction: Direction::Right,
+		};
+		let trace = trace_beams(&grid, entry);
+		assert_eq!(
+			trace.energized.to_string(),
+			"######....\n.#...#....\n.#...#####\n.#...##...\n.#...##...\n.#...\
+			 ##...\n.#..####..\n########..\n.#######..\n.#...#.#..\n"
+		);
+		let headings = |r, c| trace.headings(r, c).collect::<Vec<_>>();
+		assert_eq!(headings(0, 0), [Direction::Right]);
+		assert_eq!(headings(0, 1), [Direction::Up, Direction::Down]);
+		assert_eq!(headings(0, 2), [Direction::Left]);
+		assert_eq!(headings(0, 6), []);
+		assert_eq!(headings(7, 1), [Direction::Right, Direction::Left]);
+	}
 
-		let head = Head {
+	#[test]
+	fn two_best_provided()
+	{
+		let grid = parse_grid(PROVIDED).unwrap();
+		let entry = Entry {
 			row: 0,
 			col: 3,
-			direction: SOUTH,
+			direction: Direction::Down,
 		};
-		let energy = energize_grid(&mut grid, num_rows, num_cols, head);
-		assert_eq!(energy, 51);
+		assert_eq!(trace_beams(&grid, entry).num_energized(), 51);
+		let expected = Some(BestEntry {
+			entry,
+			num_energized: 51,
+		});
+		for num_threads in [1, 2, 3, 64]
+		{
+			assert_eq!(find_best_entry(&grid, num_threads), expected);
+		}
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 51);
+		assert_eq!(Solution::two(PROVIDED), 51);
+	}
+
+	#[test]
+	fn test_walls_and_prisms()
+	{
+		let energized = |input: &str, direction| {
+			let grid = parse_grid(input).unwrap();
+			let entry = Entry {
+				row: 0,
+				col: 0,
+				direction,
+			};
+			trace_beams(&grid, entry).energized.to_string()
+		};
+		assert_eq!(energized("..#.\n", Direction::Right), "##..\n");
+		assert_eq!(energized(".v.\n...\n", Direction::Right), "##.\n.#.\n");
+		assert_eq!(energized(".<.\n", Direction::Right), "#..\n");
+		assert_eq!(energized(">.\n..\n", Direction::Down), "##\n..\n");
+		assert_eq!(energized("^.\n", Direction::Right), "#.\n");
+		assert_eq!(
+			energized(".|.\n.>#\n...\n", Direction::Right),
+			"##.\n.#.\n...\n"
+		);
+	}
+
+	#[test]
+	fn test_empty()
+	{
+		let empty = &COMPONENTS[EMPTY as usize];
+		assert_eq!(empty.symbol, '.');
+		assert_eq!(empty.outgoing, [NORTH, EAST, SOUTH, WEST]);
+		assert_eq!(component_index('.'), Some(EMPTY));
+	}
+
+	#[test]
+	fn test_parse_errors()
+	{
+		assert_eq!(
+			parse_grid("..\n.x\n").map_err(|e| (e.line, e.col)),
+			Err((2, 2))
+		);
+		assert_eq!(
+			parse_grid("...\n..\n").map_err(|e| (e.line, e.col)),
+			Err((2, 1))
+		);
+		assert_eq!(parse_grid("").map_err(|e| (e.line, e.col)), Err((1, 1)));
+		assert!(parse_grid("#^>v<\n./\\-|\n").is_ok());
+	}
+
+	#[test]
+	fn test_edge_entries()
+	{
+		assert_eq!(edge_entries(3, 2).len(), 10);
+		assert_eq!(edge_entries(0, 5), []);
+		assert_eq!(find_best_entry(&Grid::new(0, 0, 0), 4), None);
 	}
 }
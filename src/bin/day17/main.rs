@@ -1,356 +1,192 @@
 //!
 
-use aoc2023::run;
-
-const INPUT: &str = include_str!("input.txt");
-
-const GRID_SIZE: usize = 192;
-const MAX_COST: u16 = 9;
-const BUFFER_SIZE: usize = GRID_SIZE * 50;
+use aoc2023::geometry::{self, Direction};
+use aoc2023::grid::Grid;
+use aoc2023::parse::ParseError;
+use aoc2023::search::{Queue, Search};
+use aoc2023::solver::{Part, Solver};
+use aoc2023::{load_input, run};
+use smallvec::SmallVec;
 
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> u16
-{
-	let mut cost_grid = [[0; GRID_SIZE]; GRID_SIZE];
-	let (num_rows, num_cols) = parse_grid(&mut cost_grid, input);
+pub struct Solution;
 
-	find_least_cost::<0, 2>(&cost_grid, num_rows, num_cols)
-}
-
-fn two(input: &str) -> u16
+impl Solver for Solution
 {
-	let mut cost_grid = [[0; GRID_SIZE]; GRID_SIZE];
-	let (num_rows, num_cols) = parse_grid(&mut cost_grid, input);
-
-	find_least_cost::<3, 6>(&cost_grid, num_rows, num_cols)
-}
+	type Parsed<'a> = Grid<u8>;
+	type Answer = u16;
 
-fn parse_grid(
-	grid: &mut [[u8; GRID_SIZE]; GRID_SIZE],
-	input: &str,
-) -> (usize, usize)
-{
-	let lines = input.lines().filter(|x| !x.is_empty()).enumerate();
-	let mut num_rows = 0;
-	let mut num_cols = 0;
-	for (r, line) in lines
+	fn parse(input: &str) -> Result<Grid<u8>, ParseError>
 	{
-		num_rows = num_rows.max(r + 1);
-		num_cols = line.as_bytes().len();
-		for (c, digit) in line.as_bytes().iter().enumerate()
+		let grid = Grid::try_parse(input, "a digit", |x| {
+			x.is_ascii_digit().then(|| x as u8 - b'0')
+		})?;
+		if grid.height() == 0
+		{
+			return Err(ParseError::new(1, 1, "expected a city map"));
+		}
+		if grid.height() > 255 || grid.width() > 255
 		{
-			grid[r][c] = *digit - b'0';
+			return Err(ParseError::new(
+				1,
+				1,
+				"city maps larger than 255x255 are not supported",
+			));
 		}
+		Ok(grid)
 	}
-	(num_rows, num_cols)
-}
-
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Point
-{
-	row: u8,
-	col: u8,
-}
 
-impl Point
-{
-	fn get<T: Copy>(self, grid: &[[T; GRID_SIZE]; GRID_SIZE]) -> T
+	fn part_one(cost_grid: &Grid<u8>) -> u16
 	{
-		let r = self.row as usize;
-		let c = self.col as usize;
-		grid[r][c]
+		Self::try_part(cost_grid, Part::One)
+			.unwrap_or_else(|error| panic!("{error}"))
 	}
 
-	fn step(
-		self,
-		direction: Direction,
-		num_rows: usize,
-		num_cols: usize,
-	) -> Option<Point>
+	fn part_two(cost_grid: &Grid<u8>) -> u16
 	{
-		match direction
-		{
-			Direction::East if self.col as usize + 1 < num_cols =>
-			{
-				Some(Point {
-					row: self.row,
-					col: self.col + 1,
-				})
-			}
-			Direction::South if self.row as usize + 1 < num_rows =>
-			{
-				Some(Point {
-					row: self.row + 1,
-					col: self.col,
-				})
-			}
-			Direction::West if self.col > 0 => Some(Point {
-				row: self.row,
-				col: self.col - 1,
-			}),
-			Direction::North if self.row > 0 => Some(Point {
-				row: self.row - 1,
-				col: self.col,
-			}),
-			_ => None,
-		}
+		Self::try_part(cost_grid, Part::Two)
+			.unwrap_or_else(|error| panic!("{error}"))
 	}
-}
-
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
-enum Direction
-{
-	#[default]
-	East,
-	South,
-	West,
-	North,
-}
-
-const DIRECTION_LEN: usize = 2;
 
-impl Direction
-{
-	fn turn_left(self) -> Direction
+	/// Maps that are too narrow or too short for the crucible's runs have no
+	/// route at all.
+	fn try_part(cost_grid: &Grid<u8>, part: Part) -> Result<u16, String>
 	{
-		match self
+		let (route, min_run, max_run) = match part
 		{
-			Direction::East => Direction::North,
-			Direction::South => Direction::East,
-			Direction::West => Direction::South,
-			Direction::North => Direction::West,
-		}
+			Part::One => (find_route::<0, 2>(cost_grid), 1, 3),
+			Part::Two => (find_route::<3, 6>(cost_grid), 4, 10),
+		};
+		route.map(|route| route.heat_loss).ok_or_else(|| {
+			format!(
+				"no route reaches the bottom right in runs of {min_run} to \
+				 {max_run} blocks"
+			)
+		})
 	}
 
-	fn turn_right(self) -> Direction
+	fn render(cost_grid: &Grid<u8>, part: Part) -> Option<String>
 	{
-		match self
+		let route = match part
 		{
-			Direction::East => Direction::South,
-			Direction::South => Direction::West,
-			Direction::West => Direction::North,
-			Direction::North => Direction::East,
-		}
+			Part::One => find_route::<0, 2>(cost_grid),
+			Part::Two => find_route::<3, 6>(cost_grid),
+		}?;
+		Some(render_route(cost_grid, &route).to_string())
 	}
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Explorer
-{
-	at: Point,
-	facing: Direction,
-}
+type Point = geometry::Point<u8>;
 
-impl Explorer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Explorer
 {
-	fn get<T: Copy>(
-		self,
-		grid: &[[[T; DIRECTION_LEN]; GRID_SIZE]; GRID_SIZE],
-	) -> T
-	{
-		let r = self.at.row as usize;
-		let c = self.at.col as usize;
-		let d = (self.facing as usize) % 2;
-		grid[r][c][d]
-	}
-
-	fn set<T: Copy>(
-		self,
-		grid: &mut [[[T; DIRECTION_LEN]; GRID_SIZE]; GRID_SIZE],
-		value: T,
-	)
-	{
-		let r = self.at.row as usize;
-		let c = self.at.col as usize;
-		let d = (self.facing as usize) % 2;
-		grid[r][c][d] = value;
-	}
+	pub at: Point,
+	pub facing: Direction,
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
-struct Candidate
+/// The way the crucible goes, one block at a time: every block it enters
+/// (so not the starting block) and the direction it moved to get there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route
 {
-	rank: u16,
-	explorer: Explorer,
+	pub heat_loss: u16,
+	pub steps: Vec<Explorer>,
 }
 
-const MAX_INITIAL_SHORTLIST_LEN: usize = 1024;
-
-fn find_least_cost<const MIN_STRAIN: usize, const MAX_EXTRA_STRAIN: usize>(
-	cost_grid: &[[u8; GRID_SIZE]; GRID_SIZE],
-	num_rows: usize,
-	num_cols: usize,
-) -> u16
+/// The cost grid with the blocks of `route` replaced by arrows.
+pub fn render_route(cost_grid: &Grid<u8>, route: &Route) -> Grid<char>
 {
-	let start = Point { row: 0, col: 0 };
-	let target = Point {
-		row: (num_rows - 1) as u8,
-		col: (num_cols - 1) as u8,
-	};
-	let manhattan_distance = |from: Point, to: Point| {
-		let dr = (to.row as i32 - from.row as i32).abs() as u16;
-		let dc = (to.col as i32 - from.col as i32).abs() as u16;
-		dr + dc
-	};
-	let upper_bound = manhattan_distance(start, target) * MAX_COST;
-	let mut dist = [[[upper_bound; DIRECTION_LEN]; GRID_SIZE]; GRID_SIZE];
-	let mut buffer = [Candidate::default(); BUFFER_SIZE];
-	let mut shortlist_start = 0;
-	let mut shortlist_end = 0;
-	let mut shortlist_rank_threshold = 0;
-	let mut len = 0;
-
-	for facing in [Direction::East, Direction::South]
+	let mut grid = cost_grid.map(|&x| char::from(b'0' + x));
+	for step in &route.steps
 	{
-		let explorer = Explorer { at: start, facing };
-		explorer.set(&mut dist, 0);
-		buffer[len] = Candidate { explorer, rank: 0 };
-		len += 1;
+		grid[step.at.row as usize][step.at.col as usize] = step.facing.arrow();
 	}
+	grid
+}
 
-	while len > 0
+/// The crucible turns, then moves between `MIN_STRAIN + 1` and
+/// `MIN_STRAIN + MAX_EXTRA_STRAIN + 1` blocks in a straight line. An A*
+/// search over where it stops and which way it faces finds the least heat
+/// loss; heat losses are small, so a bucket queue works well.
+fn find_route<const MIN_STRAIN: usize, const MAX_EXTRA_STRAIN: usize>(
+	cost_grid: &Grid<u8>,
+) -> Option<Route>
+{
+	let (Ok(num_rows), Ok(num_cols)) = (
+		u8::try_from(cost_grid.height()),
+		u8::try_from(cost_grid.width()),
+	)
+	else
 	{
-		debug_assert!(shortlist_start <= shortlist_end);
-		debug_assert!(shortlist_end <= len);
-		if shortlist_start == shortlist_end
-		{
-			buffer.copy_within(shortlist_end..len, 0);
-			len -= shortlist_end;
-			if len == 0
-			{
-				break;
-			}
-			shortlist_start = 0;
-			shortlist_end = len.min(MAX_INITIAL_SHORTLIST_LEN);
-			buffer[0..len].select_nth_unstable(shortlist_end - 1);
-			buffer[0..shortlist_end].sort_unstable();
-			shortlist_rank_threshold = buffer[shortlist_end - 1].rank;
-		}
-
-		let Candidate {
-			explorer: curr,
-			rank,
-		} = buffer[shortlist_start];
-		shortlist_start += 1;
-
-		let current_dist = curr.get(&dist);
-		if rank != current_dist
-		{
-			continue;
-		}
-
-		'withfacings: for facing in
-			[curr.facing.turn_left(), curr.facing.turn_right()]
+		unreachable!("rejected by parse");
+	};
+	let start = Point::new(0, 0);
+	let target = Point::new(num_rows - 1, num_cols - 1);
+	let min_cost = u32::from(*cost_grid.iter().min().unwrap());
+
+	let starts = [Direction::Right, Direction::Down]
+		.map(|facing| Explorer { at: start, facing });
+	let neighbours = |curr: &Explorer| {
+		let mut next = SmallVec::<[(Explorer, u32); 16]>::new();
+		for facing in [curr.facing.turn_left(), curr.facing.turn_right()]
 		{
-			let mut next = Explorer {
-				at: curr.at,
-				facing,
-			};
-			let mut cost = current_dist;
-			for _ in 0..MIN_STRAIN
+			let mut at = curr.at;
+			let mut cost = 0;
+			for num_steps in 1..=(MIN_STRAIN + MAX_EXTRA_STRAIN + 1)
 			{
-				let Some(at) = next.at.step(facing, num_rows, num_cols)
+				let Some(step) = at.step_within(facing, num_rows, num_cols)
 				else
 				{
-					continue 'withfacings;
+					break;
 				};
-				next.at = at;
-				cost += at.get(cost_grid) as u16;
-			}
-			for _ in 0..=MAX_EXTRA_STRAIN
-			{
-				let Some(at) = next.at.step(facing, num_rows, num_cols)
-				else
-				{
-					continue 'withfacings;
-				};
-				next.at = at;
-				cost += at.get(cost_grid) as u16;
-
-				if cost < next.get(&dist)
+				at = step;
+				cost += u32::from(cost_grid[at.row as usize][at.col as usize]);
+				if num_steps > MIN_STRAIN
 				{
-					next.set(&mut dist, cost);
-
-					let rank = cost;
-					if rank < shortlist_rank_threshold
-					{
-						if shortlist_start > 0
-						{
-							let n = buffer[shortlist_start..shortlist_end]
-								.partition_point(|&c| c.rank < rank);
-							let shortlist_mid = shortlist_start + n;
-							shortlist_start -= 1;
-							buffer[shortlist_start..shortlist_mid]
-								.copy_within(1..(n + 1), 0);
-							buffer[shortlist_mid - 1] = Candidate {
-								explorer: next,
-								rank,
-							};
-						}
-						else
-						{
-							buffer[len] = buffer[shortlist_end];
-							len += 1;
-							let i = buffer[shortlist_start..shortlist_end]
-								.partition_point(|&c| c.rank < rank);
-							let shortlist_mid = shortlist_start + i;
-							let n = shortlist_end - shortlist_mid;
-							shortlist_end += 1;
-							if n > 0
-							{
-								buffer[shortlist_mid..shortlist_end]
-									.copy_within(0..n, 1);
-							}
-							buffer[shortlist_mid] = Candidate {
-								explorer: next,
-								rank,
-							};
-						}
-					}
-					else
-					{
-						buffer[len] = Candidate {
-							explorer: next,
-							rank,
-						};
-						len += 1;
-					}
+					next.push((Explorer { at, facing }, cost));
 				}
 			}
 		}
-	}
-
-	dist[target.row as usize][target.col as usize]
-		.into_iter()
-		.min()
-		.unwrap()
-}
-
-#[allow(unused)]
-#[cfg(debug_assertions)]
-fn print_dist(
-	grid: &[[[u16; DIRECTION_LEN]; GRID_SIZE]; GRID_SIZE],
-	num_rows: usize,
-	num_cols: usize,
-)
-{
-	println!();
-	for row in &grid[0..num_rows]
+		next
+	};
+	let heuristic = |explorer: &Explorer| {
+		let Point { row, col } = explorer.at;
+		(u32::from(target.row - row) + u32::from(target.col - col)) * min_cost
+	};
+	let index = |explorer: &Explorer| {
+		let Point { row, col } = explorer.at;
+		let d = usize::from(explorer.facing.is_vertical());
+		(usize::from(row) * cost_grid.width() + usize::from(col)) * 2 + d
+	};
+	let found = Search::new(neighbours)
+		.heuristic(heuristic)
+		.queue(Queue::Buckets)
+		.dense(cost_grid.width() * cost_grid.height() * 2, index)
+		.run(starts, |explorer| explorer.at == target)?;
+
+	let mut steps = Vec::new();
+	for (from, to) in found.path.iter().zip(&found.path[1..])
 	{
-		for cell in &row[0..num_cols]
+		let mut at = from.at;
+		while at != to.at
 		{
-			print!("H{:04}", cell[0]);
-			print!("V{:04}", cell[1]);
-			print!(" ");
+			at = at.step(to.facing);
+			steps.push(Explorer {
+				at,
+				facing: to.facing,
+			});
 		}
-		println!();
 	}
-	println!();
+	Some(Route {
+		heat_loss: found.cost as u16,
+		steps,
+	})
 }
 
 #[cfg(test)]
@@ -365,19 +201,125 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 102);
+		assert_eq!(Solution::one(PROVIDED), 102);
 	}
 
 	#[test]
 	fn one_horizontal()
 	{
-		assert_eq!(one("0123"), 6);
+		assert_eq!(Solution::one("0123"), 6);
 	}
 
 	#[test]
 	fn one_vertical()
 	{
-		assert_eq!(one("07\n18\n19\n11"), 4);
+		assert_eq!(Solution::one("07\n18\n19\n11"), 4);
+	}
+
+	#[test]
+	fn one_route()
+	{
+		let cost_grid = Solution::parse("07\n18\n19\n11").unwrap();
+		let route = find_route::<0, 2>(&cost_grid).unwrap();
+		assert_eq!(route.steps.len(), 4);
+		assert_eq!(
+			render_route(&cost_grid, &route).to_string(),
+			"07\nv8\nv9\nv>\n"
+		);
+	}
+
+	/// The route keeps to the crucible's limits and loses as much heat as
+	/// reported.
+	fn check_route(
+		cost_grid: &Grid<u8>,
+		route: &Route,
+		min_run: usize,
+		max_run: usize,
+	)
+	{
+		let mut prev = Point::new(0, 0);
+		let mut heat_loss = 0;
+		for step in &route.steps
+		{
+			assert_eq!(prev.step(step.facing), step.at);
+			heat_loss += u16::from(
+				cost_grid[step.at.row as usize][step.at.col as usize],
+			);
+			prev = step.at;
+		}
+		assert_eq!(heat_loss, route.heat_loss);
+		let target = Point::new(
+			cost_grid.height() as u8 - 1,
+			cost_grid.width() as u8 - 1,
+		);
+		assert_eq!(prev, target);
+		let runs = route.steps.chunk_by(|a, b| a.facing == b.facing);
+		for run in runs
+		{
+			assert!((min_run..=max_run).contains(&run.len()), "{run:?}");
+		}
+		let rendered = render_route(cost_grid, route);
+		for step in &route.steps
+		{
+			assert_eq!(
+				rendered[step.at.row as usize][step.at.col as usize],
+				step.facing.arrow(),
+				"{step:?}"
+			);
+		}
+		let num_arrows = rendered
+			.iter()
+			.filter(|&&x| Direction::from_arrow(x).is_some())
+			.count();
+		assert_eq!(num_arrows, route.steps.len());
+	}
+
+	#[test]
+	fn both_provided_routes()
+	{
+		let cost_grid = Solution::parse(PROVIDED).unwrap();
+		let route = find_route::<0, 2>(&cost_grid).unwrap();
+		assert_eq!(route.heat_loss, 102);
+		check_route(&cost_grid, &route, 1, 3);
+		let route = find_route::<3, 6>(&cost_grid).unwrap();
+		assert_eq!(route.heat_loss, 94);
+		check_route(&cost_grid, &route, 4, 10);
+	}
+
+	#[test]
+	fn one_large_uniform()
+	{
+		let line = "1".repeat(141);
+		let input = vec![line.as_str(); 141].join("\n");
+		assert_eq!(Solution::one(&input), 280);
+	}
+
+	#[test]
+	fn no_route()
+	{
+		let cost_grid = Solution::parse("12\n34").unwrap();
+		assert_eq!(Solution::try_part(&cost_grid, Part::One), Ok(6));
+		assert_eq!(
+			Solution::try_part(&cost_grid, Part::Two),
+			Err(
+				"no route reaches the bottom right in runs of 4 to 10 blocks"
+					.to_string()
+			)
+		);
+		let cost_grid = Solution::parse("12345").unwrap();
+		assert_eq!(find_route::<0, 2>(&cost_grid), None);
+		assert_eq!(Solution::try_part(&cost_grid, Part::Two), Ok(14));
+	}
+
+	#[test]
+	fn render_parts()
+	{
+		let cost_grid = Solution::parse("07\n18\n19\n11").unwrap();
+		assert_eq!(
+			Solution::render(&cost_grid, Part::One).as_deref(),
+			Some("07\nv8\nv9\nv>\n")
+		);
+		assert_eq!(Solution::render(&cost_grid, Part::Two), None);
 	}
 
 	#[test]
@@ -389,12 +331,12 @@ mod tests
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 94);
+		assert_eq!(Solution::two(PROVIDED), 94);
 	}
 
 	#[test]
 	fn two_provided2()
 	{
-		assert_eq!(two(PROVIDED2), 71);
+		assert_eq!(Solution::two(PROVIDED2), 71);
 	}
 }
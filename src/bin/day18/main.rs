@@ -1,176 +1,171 @@
 //!
 
-use aoc2023::run;
+use aoc2023::geometry::{self, Direction};
+use aoc2023::parse::{self, ParseError};
+use aoc2023::solver::{Part, Solver};
+use aoc2023::{load_input, run};
 use parse_display::{Display, FromStr};
 use std::str::FromStr;
 
-const INPUT: &str = include_str!("input.txt");
-
-const GRID_SIZE: usize = 1024;
-
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> usize
+pub struct Solution;
+
+impl Solver for Solution
 {
-	let mut grid = [[0u8; GRID_SIZE]; GRID_SIZE];
-	let center = Point {
-		row: GRID_SIZE / 2,
-		col: GRID_SIZE / 2,
-	};
-	let mut digger = center;
-	let mut topleft = center;
-	let mut bottomright = center;
-	for line in input.lines().filter(|x| !x.is_empty())
+	type Parsed<'a> = Vec<Instruction>;
+	type Answer = i64;
+
+	fn parse(input: &str) -> Result<Vec<Instruction>, ParseError>
 	{
-		let instruction = Instruction::from_str(line).unwrap();
-		let Instruction {
-			direction,
-			distance,
-			color: _,
-		} = instruction;
-		for _ in 0..distance
-		{
-			digger.step(direction);
-			grid[digger.row][digger.col] = 1;
-		}
-		topleft.row = topleft.row.min(digger.row);
-		topleft.col = topleft.col.min(digger.col);
-		bottomright.row = bottomright.row.max(digger.row);
-		bottomright.col = bottomright.col.max(digger.col);
+		parse::lines(input)
+			.map(|line| parse_instruction(input, line))
+			.collect()
 	}
-	debug_print_grid(&grid, topleft, bottomright);
 
-	grid[topleft.row - 1].fill(2);
-	grid[bottomright.row + 1].fill(2);
-	for r in topleft.row..=bottomright.row
+	fn part_one(instructions: &Vec<Instruction>) -> i64
 	{
-		grid[r][topleft.col - 1] = 2;
-		grid[r][bottomright.col + 1] = 2;
+		Self::try_part(instructions, Part::One)
+			.unwrap_or_else(|error| panic!("{error}"))
 	}
-	let mut any_changes = true;
-	while any_changes
+
+	fn part_two(instructions: &Vec<Instruction>) -> i64
 	{
-		any_changes = false;
-		for r in topleft.row..=bottomright.row
+		Self::try_part(instructions, Part::Two)
+			.unwrap_or_else(|error| panic!("{error}"))
+	}
+
+	/// Each part reads the plan its own way, and only that reading has to
+	/// end where it started.
+	fn try_part(
+		instructions: &Vec<Instruction>,
+		part: Part,
+	) -> Result<i64, String>
+	{
+		let (step, plan): (fn(&Instruction) -> Step, _) = match part
+		{
+			Part::One => (|x| x.step, "dig plan"),
+			Part::Two => (|x| x.hex_step, "dig plan in the colors"),
+		};
+		let steps = instructions.iter().map(step);
+		let end = trace(steps.clone()).last().unwrap_or_default();
+		if end != Point::default()
 		{
-			for c in topleft.col..=bottomright.col
-			{
-				if grid[r][c] > 0
-				{
-					continue;
-				}
-				if grid[r - 1][c] == 2
-					|| grid[r + 1][c] == 2
-					|| grid[r][c - 1] == 2
-					|| grid[r][c + 1] == 2
-				{
-					grid[r][c] = 2;
-					any_changes = true;
-				}
-			}
+			return Err(format!(
+				"the {plan} ends {} rows down and {} columns right of where \
+				 it started",
+				end.row, end.col
+			));
 		}
+		Ok(lagoon_volume(steps))
 	}
-
-	// for r in topleft.row..=bottomright.row
-	// {
-	// 	for c in topleft.col..=bottomright.col
-	// 	{
-	// 		if grid[r][c] == 0
-	// 		{
-	// 			grid[r][c] = 1;
-	// 		}
-	// 	}
-	// }
-
-	debug_print_grid(&grid, topleft, bottomright);
-	grid[topleft.row..=bottomright.row]
-		.iter()
-		.map(|row| {
-			row[topleft.col..=bottomright.col]
-				.iter()
-				.filter(|&&x| x < 2)
-				.count()
-		})
-		.sum()
 }
 
-fn two(input: &str) -> usize
+type Point = geometry::Point<i64>;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Step
 {
-	input.len() * 0
+	direction: Direction,
+	distance: i64,
 }
 
-#[derive(Debug, Clone, Copy, Default, Display, FromStr)]
-enum Direction
+/// A line of the dig plan, read both as written and with the distance and
+/// direction decoded from the color.
+#[derive(Debug, Clone, Copy)]
+pub struct Instruction
 {
-	#[default]
-	#[display("U")]
-	Up,
-	#[display("D")]
-	Down,
-	#[display("L")]
-	Left,
-	#[display("R")]
-	Right,
+	step: Step,
+	hex_step: Step,
 }
 
 #[derive(Debug, Clone, Default, Display, FromStr)]
 #[display("{direction} {distance} (#{color})")]
-struct Instruction
+struct Line
 {
 	direction: Direction,
-	distance: i32,
+	distance: i64,
 	color: String,
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Point
+fn parse_instruction(input: &str, line: &str)
+	-> Result<Instruction, ParseError>
 {
-	row: usize,
-	col: usize,
+	let Line {
+		direction,
+		distance,
+		color,
+	} = Line::from_str(line).map_err(|_| {
+		ParseError::at(
+			input,
+			line,
+			"expected '<U|D|L|R> <distance> (#<color>)'",
+		)
+	})?;
+	let color_offset = line.len() - color.len() - 2;
+	let color_error = || {
+		ParseError::at(
+			input,
+			&line[color_offset..],
+			"expected five hex digits for the distance and a direction from 0 \
+			 to 3",
+		)
+	};
+	if color.len() != 6 || !color.bytes().all(|x| x.is_ascii_hexdigit())
+	{
+		return Err(color_error());
+	}
+	let hex_distance = i64::from_str_radix(&color[..5], 16).unwrap();
+	let hex_direction = match &color[5..]
+	{
+		"0" => Direction::Right,
+		"1" => Direction::Down,
+		"2" => Direction::Left,
+		"3" => Direction::Up,
+		_ => return Err(color_error()),
+	};
+	Ok(Instruction {
+		step: Step {
+			direction,
+			distance,
+		},
+		hex_step: Step {
+			direction: hex_direction,
+			distance: hex_distance,
+		},
+	})
 }
 
-impl Point
+/// The corners the digger passes, starting after the first step.
+fn trace(steps: impl Iterator<Item = Step>) -> impl Iterator<Item = Point>
 {
-	fn step(&mut self, direction: Direction)
-	{
-		match direction
-		{
-			Direction::Up => self.row -= 1,
-			Direction::Down => self.row += 1,
-			Direction::Left => self.col -= 1,
-			Direction::Right => self.col += 1,
-		}
-	}
+	steps.scan(Point::default(), |digger, step| {
+		*digger = digger.step_by(step.direction, step.distance);
+		Some(*digger)
+	})
 }
 
-fn debug_print_grid(
-	grid: &[[u8; GRID_SIZE]; GRID_SIZE],
-	topleft: Point,
-	bottomright: Point,
-)
+/// The number of cubic meters dug out, trench included. The shoelace
+/// formula gives the area enclosed by the middle of the trench, and Pick's
+/// theorem turns that into the number of interior cells, to which we add
+/// the cells of the trench itself.
+fn lagoon_volume(steps: impl Iterator<Item = Step> + Clone) -> i64
 {
-	println!();
-	let mut buffer = String::new();
-	for row in &grid[(topleft.row - 1)..(bottomright.row + 2)]
+	let trench_length: i64 = steps.clone().map(|step| step.distance).sum();
+	let mut prev = Point::default();
+	let mut twice_area = 0;
+	for next in trace(steps)
 	{
-		buffer.clear();
-		for cell in &row[(topleft.col - 1)..(bottomright.col + 2)]
-		{
-			match *cell
-			{
-				0 => buffer.push('.'),
-				1 => buffer.push('#'),
-				2 => buffer.push('~'),
-				_ => buffer.push('?'),
-			}
-		}
-		println!("{buffer}");
+		twice_area += prev.row * next.col - next.row * prev.col;
+		prev = next;
 	}
-	println!();
+	let area = twice_area.abs() / 2;
+	let num_interior = area - trench_length / 2 + 1;
+	num_interior + trench_length
 }
 
 #[cfg(test)]
@@ -184,12 +179,62 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 62);
+		assert_eq!(Solution::one(PROVIDED), 62);
+	}
+
+	#[test]
+	fn both_square()
+	{
+		const SQUARE: &str =
+			"R 2 (#000020)\nD 2 (#000021)\nL 2 (#000022)\nU 2 (#000023)\n";
+		assert_eq!(Solution::one(SQUARE), 9);
+		assert_eq!(Solution::two(SQUARE), 9);
+	}
+
+	#[test]
+	fn parse_errors()
+	{
+		let error = |input| Solution::parse(input).unwrap_err().to_string();
+		assert_eq!(
+			error("R 2 (#000020)\nD 2 (#00002x)\n"),
+			"input line 2 col 6: expected five hex digits for the distance \
+			 and a direction from 0 to 3"
+		);
+		assert_eq!(
+			error("R 2 (#000024)\n"),
+			"input line 1 col 6: expected five hex digits for the distance \
+			 and a direction from 0 to 3"
+		);
+	}
+
+	#[test]
+	fn both_unclosed()
+	{
+		let solve = |input, part| {
+			let instructions = Solution::parse(input).unwrap();
+			Solution::try_part(&instructions, part)
+		};
+		assert_eq!(
+			solve("R 2 (#000020)\nL 1 (#000022)\n", Part::One),
+			Err(
+				"the dig plan ends 0 rows down and 1 columns right of where \
+				 it started"
+					.to_string()
+			)
+		);
+		let there_and_back = "R 2 (#000020)\nL 2 (#000021)\n";
+		assert_eq!(solve(there_and_back, Part::One), Ok(3));
+		assert_eq!(
+			solve(there_and_back, Part::Two),
+			Err("the dig plan in the colors ends 2 rows down and 2 columns \
+			     right of where it started"
+				.to_string())
+		);
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 0);
+		assert_eq!(Solution::two(PROVIDED), 952408144115);
 	}
 }
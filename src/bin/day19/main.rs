@@ -1,11 +1,13 @@
 //!
 
-use aoc2023::run;
+use aoc2023::dot::Dot;
+use aoc2023::parse::{self, ParseError};
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 use parse_display::{Display, FromStr};
+use smallvec::SmallVec;
 use std::str::FromStr;
 
-const INPUT: &str = include_str!("input.txt");
-
 const MAX_NUM_NODES: usize = 2 * 1024;
 const INNER_INDEX: usize = MAX_NUM_NODES / 2;
 const ACCEPTANCE_INDEX: usize = MAX_NUM_NODES;
@@ -16,73 +18,163 @@ const MAX_VALUE: u16 = 4000;
 
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> u32
+pub struct Solution;
+
+impl Solver for Solution
 {
-	let mut lines = input.lines();
-	let mut names = [""; MAX_NUM_NODES / 2];
-	let mut nodes = [Node::default(); MAX_NUM_NODES];
-	load_nodes(&mut nodes, &mut names, &mut lines);
-	solve_parts(&nodes, lines)
+	type Parsed<'a> = System<'a>;
+	type Answer = u64;
+
+	fn parse(input: &str) -> Result<System<'_>, ParseError>
+	{
+		let mut lines = input.lines();
+		let mut names = [""; MAX_NUM_NODES / 2];
+		let mut nodes = [Node::default(); MAX_NUM_NODES];
+		load_nodes(input, &mut nodes, &mut names, &mut lines)?;
+		let parts = lines
+			.take_while(|line| !line.is_empty())
+			.map(|line| {
+				Part::from_str(line).map_err(|_| {
+					ParseError::at(input, line, "expected '{x=N,m=N,a=N,s=N}'")
+				})
+			})
+			.collect::<Result<_, _>>()?;
+		let names = names.into_iter().take_while(|x| !x.is_empty()).collect();
+		Ok(System {
+			nodes,
+			names,
+			parts,
+		})
+	}
+
+	fn part_one(system: &System) -> u64
+	{
+		solve_parts(&system.nodes, &system.parts).into()
+	}
+
+	fn part_two(system: &System) -> u64
+	{
+		accepted_hyperrectangles(system)
+			.iter()
+			.map(Hyperrectangle::num_combinations)
+			.sum()
+	}
+
+	fn to_dot(system: &System) -> Option<Dot>
+	{
+		Some(system.to_dot())
+	}
 }
 
-fn two(input: &str) -> usize
+#[derive(Debug)]
+pub struct System<'a>
 {
-	let lines = input.lines();
-	let mut names = [""; MAX_NUM_NODES / 2];
-	let mut nodes = [Node::default(); MAX_NUM_NODES];
-	load_nodes(&mut nodes, &mut names, lines);
-	solve_everything(&nodes)
+	nodes: [Node; MAX_NUM_NODES],
+	/// The names of the workflows, indexed like their first node.
+	names: Vec<&'a str>,
+	parts: Vec<Part>,
+}
+
+impl System<'_>
+{
+	/// The nodes reachable from `in`, each a single comparison, with the
+	/// edge taken when it holds labelled "yes".
+	fn to_dot(&self) -> Dot
+	{
+		let mut dot = Dot::digraph("day19");
+		dot.node(ACCEPTANCE_INDEX).label("A").color("green");
+		dot.node(REJECTANCE_INDEX).label("R").color("red");
+		let mut is_visited = [false; MAX_NUM_NODES];
+		let mut stack = vec![0];
+		while let Some(i) = stack.pop()
+		{
+			if i >= MAX_NUM_NODES || is_visited[i]
+			{
+				continue;
+			}
+			is_visited[i] = true;
+			let node = self.nodes[i];
+			let field = Field::ALL
+				.into_iter()
+				.find(|field| field.shr() == node.shr)
+				.expect("every node compares a field");
+			let condition = format!("{field}<{}", node.threshold);
+			match self.names.get(i)
+			{
+				Some(name) => dot.node(i).label(format!("{name}\n{condition}")),
+				None => dot.node(i).label(condition),
+			};
+			dot.edge(i, node.less).label("yes");
+			dot.edge(i, node.more).label("no");
+			stack.push(node.more as usize);
+			stack.push(node.less as usize);
+		}
+		dot
+	}
 }
 
 fn insert<'a: 'b, 'b>(
 	name: &'a str,
 	names: &'b mut [&'a str],
 	num_names: &'b mut usize,
-) -> usize
+) -> Option<usize>
 {
 	let i = *num_names;
-	names[i] = name;
+	*names.get_mut(i)? = name;
 	*num_names += 1;
-	i
+	Some(i)
 }
 
 fn find_or_insert<'a: 'b, 'b>(
 	name: &'a str,
 	names: &'b mut [&'a str],
 	num_names: &'b mut usize,
-) -> usize
+) -> Option<usize>
 {
 	if name == "A"
 	{
-		ACCEPTANCE_INDEX
+		Some(ACCEPTANCE_INDEX)
 	}
 	else if name == "R"
 	{
-		REJECTANCE_INDEX
+		Some(REJECTANCE_INDEX)
 	}
 	else
 	{
 		match names[0..*num_names].iter().position(|&n| n == name)
 		{
-			Some(i) => i,
+			Some(i) => Some(i),
 			None => insert(name, names, num_names),
 		}
 	}
 }
 
 fn load_nodes<'a: 'b, 'b>(
+	input: &str,
 	nodes: &'b mut [Node; MAX_NUM_NODES],
 	names: &'b mut [&'a str; MAX_NUM_NODES / 2],
 	mut lines: impl Iterator<Item = &'a str>,
-)
+) -> Result<(), ParseError>
 {
 	let mut num_names = 0;
 	let mut inner_ix = INNER_INDEX;
 
+	let too_many = |name: &str| {
+		ParseError::at(
+			input,
+			name,
+			format!(
+				"more than {} workflows are not supported",
+				MAX_NUM_NODES / 2
+			),
+		)
+	};
+
+	let mut is_defined = [false; MAX_NUM_NODES / 2];
 	find_or_insert("in", names, &mut num_names);
 
 	while let Some(line) = lines.next()
@@ -91,19 +183,51 @@ fn load_nodes<'a: 'b, 'b>(
 		{
 			break;
 		}
-		let (name, rest) = line.split_once('{').unwrap();
-		let rest = rest.trim_end_matches('}');
-		let mut i = find_or_insert(name, names, &mut num_names);
-		let (rules, last) = rest.rsplit_once(',').unwrap();
-		let last_ix = find_or_insert(last, names, &mut num_names);
+		let (name, rest) = line
+			.split_once('{')
+			.ok_or_else(|| ParseError::after(input, line, "expected '{'"))?;
+		let rest = rest
+			.strip_suffix('}')
+			.ok_or_else(|| ParseError::after(input, line, "expected '}'"))?;
+		let mut i = find_or_insert(name, names, &mut num_names)
+			.ok_or_else(|| too_many(name))?;
+		if i >= MAX_NUM_NODES / 2
+		{
+			return Err(ParseError::at(
+				input,
+				name,
+				format!("workflows cannot be named '{name}'"),
+			));
+		}
+		is_defined[i] = true;
+		let (rules, last) = rest.rsplit_once(',').ok_or_else(|| {
+			ParseError::at(input, rest, "expected a rule before the fallback")
+		})?;
+		let last_ix = find_or_insert(last, names, &mut num_names)
+			.ok_or_else(|| too_many(last))?;
 		let mut rules = rules.split(',').peekable();
 		while let Some(rule) = rules.next()
 		{
-			let (condition, then_name) = rule.split_once(':').unwrap();
-			let cond = Condition::from_str(condition).unwrap();
-			let then_ix = find_or_insert(then_name, names, &mut num_names);
+			let (condition, then_name) =
+				rule.split_once(':').ok_or_else(|| {
+					ParseError::after(input, rule, "expected ':'")
+				})?;
+			let cond = Condition::parse(input, condition)?;
+			let then_ix = find_or_insert(then_name, names, &mut num_names)
+				.ok_or_else(|| too_many(then_name))?;
 			let else_ix = if rules.peek().is_some()
 			{
+				if inner_ix == MAX_NUM_NODES
+				{
+					return Err(ParseError::at(
+						input,
+						rule,
+						format!(
+							"more than {} rules are not supported",
+							MAX_NUM_NODES - INNER_INDEX
+						),
+					));
+				}
 				let else_ix = inner_ix;
 				inner_ix += 1;
 				else_ix
@@ -114,7 +238,7 @@ fn load_nodes<'a: 'b, 'b>(
 			};
 			let (less, more) = cond.less_and_more(then_ix, else_ix);
 			nodes[i] = Node {
-				shr: cond.shr(),
+				shr: cond.field.shr(),
 				threshold: cond.threshold(),
 				less: less as u16,
 				more: more as u16,
@@ -123,15 +247,22 @@ fn load_nodes<'a: 'b, 'b>(
 		}
 	}
 
-	let num_inner = inner_ix - INNER_INDEX;
-
-	if cfg!(debug_assertions)
+	if let Some(i) = (0..num_names).find(|&i| !is_defined[i])
 	{
-		dbg!(num_names);
-		dbg!(num_inner);
+		let message = format!("workflow '{}' is never defined", names[i]);
+		// The name of any other workflow points at where it is first used.
+		return Err(
+			if i == 0
+			{
+				ParseError::new(1, 1, message)
+			}
+			else
+			{
+				ParseError::at(input, names[i], message)
+			},
+		);
 	}
 
-	let mut num_nodes = num_names + num_inner;
 	let mut selves = [0; MAX_NUM_NODES + 2];
 	for i in (0..num_names).chain(INNER_INDEX..inner_ix)
 	{
@@ -157,31 +288,18 @@ fn load_nodes<'a: 'b, 'b>(
 			if node.less == node.more
 			{
 				selves[i] = node.less;
-				num_nodes -= 1;
 				any_change = true;
 			}
 		}
 	}
-
-	if cfg!(debug_assertions)
-	{
-		dbg!(num_nodes);
-	}
+	Ok(())
 }
 
-fn solve_parts<'a>(
-	nodes: &[Node; MAX_NUM_NODES],
-	mut lines: impl Iterator<Item = &'a str>,
-) -> u32
+fn solve_parts(nodes: &[Node; MAX_NUM_NODES], parts: &[Part]) -> u32
 {
 	let mut answer = 0;
-	while let Some(line) = lines.next()
+	for part in parts
 	{
-		if line.is_empty()
-		{
-			break;
-		}
-		let part = Part::from_str(line).unwrap();
 		let part_as_u64 = part.as_u64();
 
 		let mut i = 0;
@@ -197,9 +315,82 @@ fn solve_parts<'a>(
 	answer
 }
 
-fn solve_everything(nodes: &[Node; MAX_NUM_NODES]) -> usize
+/// A box of ratings: every part whose x, m, a and s ratings (in that order)
+/// lie between `min` and `max` inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hyperrectangle
 {
-	0
+	pub min: [u16; 4],
+	pub max: [u16; 4],
+}
+
+impl Hyperrectangle
+{
+	fn num_combinations(&self) -> u64
+	{
+		self.min
+			.iter()
+			.zip(self.max)
+			.map(|(&min, max)| u64::from(max - min + 1))
+			.product()
+	}
+
+	#[cfg(test)]
+	fn contains(&self, part: Part) -> bool
+	{
+		let ratings = [part.x, part.m, part.a, part.s];
+		(0..4).all(|k| (self.min[k]..=self.max[k]).contains(&ratings[k]))
+	}
+
+	/// Splits off the ratings below `threshold` in the dimension that `shr`
+	/// selects, returning the parts below and at or above it.
+	fn split(
+		self,
+		shr: u16,
+		threshold: u16,
+	) -> (Option<Hyperrectangle>, Option<Hyperrectangle>)
+	{
+		let k = 3 - usize::from(shr / 16);
+		let mut less = self;
+		let mut more = self;
+		less.max[k] = self.max[k].min(threshold.saturating_sub(1));
+		more.min[k] = self.min[k].max(threshold);
+		(
+			Some(less).filter(|x| x.min[k] <= x.max[k]),
+			Some(more).filter(|x| x.min[k] <= x.max[k]),
+		)
+	}
+}
+
+/// The disjoint boxes of ratings from 1 to 4000 that the workflows accept,
+/// found by splitting the full box at every node on the way down.
+pub fn accepted_hyperrectangles(system: &System) -> Vec<Hyperrectangle>
+{
+	let nodes = &system.nodes;
+	let everything = Hyperrectangle {
+		min: [MIN_VALUE; 4],
+		max: [MAX_VALUE; 4],
+	};
+	let mut accepted = Vec::new();
+	let mut stack: SmallVec<[(usize, Hyperrectangle); 64]> = SmallVec::new();
+	stack.push((0, everything));
+	while let Some((i, rectangle)) = stack.pop()
+	{
+		if i == ACCEPTANCE_INDEX
+		{
+			accepted.push(rectangle);
+			continue;
+		}
+		else if i >= MAX_NUM_NODES
+		{
+			continue;
+		}
+		let node = nodes[i];
+		let (less, more) = rectangle.split(node.shr, node.threshold);
+		stack.extend(more.map(|x| (node.more as usize, x)));
+		stack.extend(less.map(|x| (node.less as usize, x)));
+	}
+	accepted
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -246,6 +437,23 @@ enum Field
 	S,
 }
 
+impl Field
+{
+	const ALL: [Field; 4] = [Field::X, Field::M, Field::A, Field::S];
+
+	/// How far a part's rating of this field is shifted within the part.
+	fn shr(self) -> u16
+	{
+		match self
+		{
+			Field::X => 48,
+			Field::M => 32,
+			Field::A => 16,
+			Field::S => 0,
+		}
+	}
+}
+
 #[derive(Debug, Clone, Copy, Display)]
 #[display("{field}{op}{value}")]
 struct Condition
@@ -255,32 +463,29 @@ struct Condition
 	value: u16,
 }
 
-impl FromStr for Condition
-{
-	type Err = ();
-
-	fn from_str(s: &str) -> Result<Self, Self::Err>
-	{
-		let (head, value) = s.split_at(2);
-		let (field, op) = head.split_at(1);
-		Ok(Condition {
-			field: field.parse().unwrap(),
-			op: op.parse().unwrap(),
-			value: value.parse().unwrap(),
-		})
-	}
-}
-
 impl Condition
 {
-	fn shr(&self) -> u16
+	/// Parses a condition such as `a<2006`, which is a slice of `input`.
+	fn parse(input: &str, s: &str) -> Result<Condition, ParseError>
 	{
-		match self.field
+		let field =
+			s.get(..1).and_then(|x| x.parse().ok()).ok_or_else(|| {
+				ParseError::at(input, s, "expected 'x', 'm', 'a' or 's'")
+			})?;
+		let rest = &s[1..];
+		let op =
+			rest.get(..1).and_then(|x| x.parse().ok()).ok_or_else(|| {
+				ParseError::at(input, rest, "expected '<' or '>'")
+			})?;
+		let value = &rest[1..];
+		match parse::parse_word(input, value, "a rating")?
 		{
-			Field::X => 48,
-			Field::M => 32,
-			Field::A => 16,
-			Field::S => 0,
+			value @ MIN_VALUE..=MAX_VALUE => Ok(Condition { field, op, value }),
+			_ => Err(ParseError::at(
+				input,
+				value,
+				format!("expected a rating from {MIN_VALUE} to {MAX_VALUE}"),
+			)),
 		}
 	}
 
@@ -343,12 +548,89 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 19114);
+		assert_eq!(Solution::one(PROVIDED), 19114);
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 167409079868000);
+		assert_eq!(Solution::two(PROVIDED), 167409079868000);
+	}
+
+	#[test]
+	fn hyperrectangles()
+	{
+		let system = Solution::parse(PROVIDED).unwrap();
+		let accepted = accepted_hyperrectangles(&system);
+		for part in &system.parts
+		{
+			let is_accepted = solve_parts(&system.nodes, &[*part]) > 0;
+			let num_containing =
+				accepted.iter().filter(|x| x.contains(*part)).count();
+			assert_eq!(num_containing, usize::from(is_accepted));
+		}
+		let everything = Hyperrectangle {
+			min: [MIN_VALUE; 4],
+			max: [MAX_VALUE; 4],
+		};
+		let rejected = everything.num_combinations()
+			- accepted
+				.iter()
+				.map(Hyperrectangle::num_combinations)
+				.sum::<u64>();
+		assert_eq!(rejected, 256000000000000 - 167409079868000);
+	}
+
+	#[test]
+	fn test_to_dot()
+	{
+		let system = Solution::parse(PROVIDED).unwrap();
+		let dot = Solution::to_dot(&system).unwrap().to_string();
+		assert!(dot.contains("\t\"0\" [label=\"in\\ns<1351\"];\n"));
+		assert!(dot.contains("[label=\"px\\na<2006\"];\n"));
+		assert!(dot.contains("[label=\"m<2091\"];\n"));
+		assert!(dot.contains(&format!("\t\"{ACCEPTANCE_INDEX}\" [label=\"A\"")));
+	}
+
+	#[test]
+	fn parse_errors()
+	{
+		let error = |input| Solution::parse(input).unwrap_err().to_string();
+		assert_eq!(
+			error("in{a<1:A,R}\npx{x=2:A,R}\n"),
+			"input line 2 col 5: expected '<' or '>'"
+		);
+		assert_eq!(
+			error("in{q<1:A,R}\n"),
+			"input line 1 col 4: expected 'x', 'm', 'a' or 's'"
+		);
+		assert_eq!(
+			error("in{a<4001:A,R}\n"),
+			"input line 1 col 6: expected a rating from 1 to 4000"
+		);
+		assert_eq!(
+			error("in{R}\n\n{x=1,m=2,a=3,s=4}\n"),
+			"input line 1 col 4: expected a rule before the fallback"
+		);
+		assert_eq!(
+			error("in{a<5:R,A}\n\n{x=1,m=2,a=3}\n"),
+			"input line 3 col 1: expected '{x=N,m=N,a=N,s=N}'"
+		);
+		assert_eq!(
+			error("in{x>1:foo,R}\n\n{x=1,m=2,a=3,s=4}\n"),
+			"input line 1 col 8: workflow 'foo' is never defined"
+		);
+		assert_eq!(
+			error("px{x>1:A,bar}\nin{x>1:px,R}\n"),
+			"input line 1 col 10: workflow 'bar' is never defined"
+		);
+		assert_eq!(
+			error("px{x>1:A,R}\n"),
+			"input line 1 col 1: workflow 'in' is never defined"
+		);
+		assert_eq!(
+			error("in{x>1:A,R}\nA{x>1:A,R}\n"),
+			"input line 2 col 1: workflows cannot be named 'A'"
+		);
 	}
 }
@@ -1,127 +1,253 @@
 //!
 
+use aoc2023::dot::Dot;
+use aoc2023::math::lcm;
+use aoc2023::parse::{self, ParseError};
 use aoc2023::ring_buffer::RingBuffer;
-use aoc2023::run;
+use aoc2023::solver::{Part, Solver};
+use aoc2023::{load_input, run};
 use smallvec::SmallVec;
 
-const INPUT: &str = include_str!("input.txt");
-
 const MAX_NUM_NODES: usize = 64;
 const MAX_NUM_SUCCESSORS: usize = 16;
 const MAX_MEMORY: usize = 128;
+const MAX_NUM_PRESSES: usize = 1 << 20;
 
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> usize
+pub struct Solution;
+
+impl Solver for Solution
 {
-	let mut nodes: [Node; MAX_NUM_NODES] =
-		std::array::from_fn(|_i| Node::default());
-	let mut names = [""; MAX_NUM_NODES];
-	load_nodes(&mut nodes, &mut names, input);
-	let mut memory = 1;
-	let mut num_lo = 0;
-	let mut num_hi = 0;
-	for _ in 0..1000
+	type Parsed<'a> = Network<'a>;
+	type Answer = usize;
+
+	fn parse(input: &str) -> Result<Network<'_>, ParseError>
 	{
-		let [l, h] = press_button(&nodes, &mut memory);
-		num_lo += l;
-		num_hi += h;
+		let mut network = Network {
+			nodes: std::array::from_fn(|_i| Node::default()),
+			names: [""; MAX_NUM_NODES],
+			rx_feeder: Ok(0),
+		};
+		load_nodes(&mut network.nodes, &mut network.names, input)?;
+		network.rx_feeder = find_rx_feeder(&network, input);
+		Ok(network)
+	}
 
-		if cfg!(debug_assertions)
+	fn part_one(network: &Network) -> usize
+	{
+		let nodes = &network.nodes;
+		let mut memory = 0;
+		let mut num_lo = 0;
+		let mut num_hi = 0;
+		for _ in 0..1000
 		{
-			// dbg!(format!("{memory:0128b}"));
+			press_button(nodes, &mut memory, |pulse| {
+				if pulse.is_high
+				{
+					num_hi += 1;
+				}
+				else
+				{
+					num_lo += 1;
+				}
+			});
 		}
+		num_lo * num_hi
 	}
-	num_lo * num_hi
-}
 
-fn two(input: &str) -> usize
-{
-	let mut nodes: [Node; MAX_NUM_NODES] =
-		std::array::from_fn(|_i| Node::default());
-	let mut names = [""; MAX_NUM_NODES];
-	load_nodes(&mut nodes, &mut names, input);
-
-	println!("### graphviz dot");
-	println!("digraph {{");
-	for i in 0..MAX_NUM_NODES
+	fn part_two(network: &Network) -> usize
 	{
-		if names[i].is_empty()
-		{
-			break;
-		}
-		match nodes[i].kind
+		count_presses_until_rx(network)
+			.unwrap_or_else(|error| panic!("{error}"))
+	}
+
+	fn try_part(network: &Network, part: Part) -> Result<usize, String>
+	{
+		match part
 		{
-			NodeKind::FlipFlop => println!("{} [color=blue]", names[i]),
-			NodeKind::Conjunction => println!("{} [color=red]", names[i]),
-			_ => (),
+			Part::One => Ok(Self::part_one(network)),
+			Part::Two => count_presses_until_rx(network),
 		}
-		print!("{}", names[i]);
-		print!(" -> {{");
-		for j in &nodes[i].successors
+	}
+
+	fn to_dot(network: &Network) -> Option<Dot>
+	{
+		Some(network.to_dot())
+	}
+}
+
+#[derive(Debug)]
+pub struct Network<'a>
+{
+	nodes: [Node; MAX_NUM_NODES],
+	names: [&'a str; MAX_NUM_NODES],
+	/// The conjunction that feeds `rx`, or why part two cannot be solved.
+	rx_feeder: Result<u8, String>,
+}
+
+impl Network<'_>
+{
+	/// Flip-flops are blue and conjunctions red, labelled with the same
+	/// `%` and `&` prefixes as in the input.
+	fn to_dot(&self) -> Dot
+	{
+		let mut dot = Dot::digraph("day20");
+		let num_nodes = self.names.iter().take_while(|x| !x.is_empty()).count();
+		for (name, node) in self.names.iter().zip(&self.nodes[..num_nodes])
 		{
-			print!(" {}", names[*j as usize]);
+			match node.kind
+			{
+				NodeKind::Button | NodeKind::Broadcast =>
+				{
+					dot.node(name).shape("box")
+				}
+				NodeKind::Output => dot.node(name).shape("doublecircle"),
+				NodeKind::FlipFlop =>
+				{
+					dot.node(name).label(format!("%{name}")).color("blue")
+				}
+				NodeKind::Conjunction =>
+				{
+					dot.node(name).label(format!("&{name}")).color("red")
+				}
+			};
 		}
-		print!(" }}");
-		match nodes[i].kind
+		for (name, node) in self.names.iter().zip(&self.nodes[..num_nodes])
 		{
-			NodeKind::FlipFlop => println!(" [color=blue]"),
-			NodeKind::Conjunction => println!(" [color=red]"),
-			_ => println!(),
+			for &j in &node.successors
+			{
+				dot.edge(name, self.names[j as usize]);
+			}
 		}
+		dot
 	}
-	println!("}}");
-	println!("### end");
+}
 
-	let rx_ix = names.iter().position(|&name| name == "rx").unwrap();
-	let pred_ix = nodes[rx_ix as usize].predecessors[0];
-	for i in &nodes[pred_ix as usize].predecessors
+/// Part two only makes sense for networks where `rx` is fed by a single
+/// conjunction; part one works for any network. Where the input shows what
+/// is wrong, the reason points there.
+fn find_rx_feeder(network: &Network, input: &str) -> Result<u8, String>
+{
+	let names = &network.names;
+	let rx_ix = names
+		.iter()
+		.position(|&name| name == "rx")
+		.ok_or("no module sends pulses to 'rx'")?;
+	let predecessors = &network.nodes[rx_ix].predecessors;
+	let [feeder_ix] = predecessors[..]
+	else
+	{
+		return Err(ParseError::at(
+			input,
+			names[rx_ix],
+			format!(
+				"'rx' should be fed by a single conjunction, not {} modules",
+				predecessors.len()
+			),
+		)
+		.to_string());
+	};
+	if network.nodes[feeder_ix as usize].kind != NodeKind::Conjunction
 	{
-		nodes[*i as usize].kind = NodeKind::Ticker;
+		let name = names[feeder_ix as usize];
+		return Err(ParseError::at(
+			input,
+			name,
+			format!("'{name}' feeds 'rx' but is not a conjunction"),
+		)
+		.to_string());
 	}
+	Ok(feeder_ix)
+}
 
-	let mut memory = 1;
+/// The number of presses before `rx` gets a low pulse. It is fed by a single
+/// conjunction, which sends one when all of its inputs last sent it a high
+/// pulse; each of those inputs does so periodically, on every multiple of
+/// some number of presses, so the answer is the LCM of those periods.
+fn count_presses_until_rx(network: &Network) -> Result<usize, String>
+{
+	let nodes = &network.nodes;
+	let names = &network.names;
+	let feeder_ix = network.rx_feeder.clone()?;
+	let feeder = &nodes[feeder_ix as usize];
+
+	let mut high_presses: SmallVec<[SmallVec<[usize; 2]>; MAX_NUM_SUCCESSORS]> =
+		feeder
+			.predecessors
+			.iter()
+			.map(|_| SmallVec::new())
+			.collect();
+	let mut memory = 0;
 	let mut num_presses = 0;
-	for _ in 0..20000
+	while high_presses.iter().any(|presses| presses.len() < 2)
 	{
 		num_presses += 1;
-		dbg!(num_presses);
-		press_button(&nodes, &mut memory);
-
-		if cfg!(debug_assertions)
+		if num_presses > MAX_NUM_PRESSES
 		{
-			// dbg!(format!("{memory:0128b}"));
+			return Err(format!(
+				"rx gets no low pulse within {MAX_NUM_PRESSES} presses"
+			));
 		}
+		press_button(nodes, &mut memory, |pulse| {
+			if pulse.recipient == feeder_ix && pulse.is_high
+			{
+				let k = feeder
+					.predecessors
+					.iter()
+					.position(|&j| j == pulse.source)
+					.unwrap();
+				if high_presses[k].last() != Some(&num_presses)
+				{
+					high_presses[k].push(num_presses);
+				}
+			}
+		});
 	}
 
-	0
+	let mut answer = 1;
+	for (&j, presses) in feeder.predecessors.iter().zip(&high_presses)
+	{
+		let first = presses[0];
+		let period = presses[1] - presses[0];
+		if first != period
+		{
+			return Err(format!(
+				"{} first sends a high pulse after {first} presses but then \
+				 every {period}",
+				names[j as usize]
+			));
+		}
+		answer = lcm(answer, period);
+	}
+	Ok(answer)
 }
 
 fn insert<'a: 'b, 'b>(
 	name: &'a str,
 	names: &'b mut [&'a str],
 	num_names: &'b mut usize,
-) -> usize
+) -> Option<usize>
 {
 	let i = *num_names;
-	names[i] = name;
+	*names.get_mut(i)? = name;
 	*num_names += 1;
-	i
+	Some(i)
 }
 
 fn find_or_insert<'a: 'b, 'b>(
 	name: &'a str,
 	names: &'b mut [&'a str],
 	num_names: &'b mut usize,
-) -> usize
+) -> Option<usize>
 {
 	match names[0..*num_names].iter().position(|&n| n == name)
 	{
-		Some(i) => i,
+		Some(i) => Some(i),
 		None => insert(name, names, num_names),
 	}
 }
@@ -130,9 +256,15 @@ fn load_nodes<'a: 'b, 'b>(
 	nodes: &'b mut [Node; MAX_NUM_NODES],
 	names: &'b mut [&'a str; MAX_NUM_NODES],
 	input: &'a str,
-)
+) -> Result<(), ParseError>
 {
-	let mut lines = input.lines().filter(|x| !x.is_empty());
+	let too_many = |name: &str| {
+		ParseError::at(
+			input,
+			name,
+			format!("more than {MAX_NUM_NODES} modules are not supported"),
+		)
+	};
 
 	let mut num_names = 0;
 
@@ -142,29 +274,52 @@ fn load_nodes<'a: 'b, 'b>(
 	nodes[0].successors.push(1);
 	nodes[1].predecessors.push(0);
 
-	while let Some(line) = lines.next()
+	for line in parse::lines(input)
 	{
-		if line.is_empty()
+		let (node_kind, line) = if let Some(line) = line.strip_prefix('%')
 		{
-			break;
+			(NodeKind::FlipFlop, line)
 		}
-		let node_kind = match line.as_bytes()[0]
+		else if let Some(line) = line.strip_prefix('&')
 		{
-			b'%' => NodeKind::FlipFlop,
-			b'&' => NodeKind::Conjunction,
-			_ => NodeKind::Broadcast,
+			(NodeKind::Conjunction, line)
+		}
+		else
+		{
+			(NodeKind::Broadcast, line)
 		};
-		let line = line.trim_start_matches(|x: char| ['%', '&'].contains(&x));
-		let (name, rest) = line.split_once(" -> ").unwrap();
-		let i = find_or_insert(name, names, &mut num_names);
+		let (name, rest) = line
+			.split_once(" -> ")
+			.ok_or_else(|| ParseError::after(input, line, "expected ' -> '"))?;
+		if node_kind == NodeKind::Broadcast && name != "broadcaster"
+		{
+			return Err(ParseError::at(
+				input,
+				line,
+				"expected '%', '&' or 'broadcaster'",
+			));
+		}
+		let i = find_or_insert(name, names, &mut num_names)
+			.ok_or_else(|| too_many(name))?;
 		nodes[i].kind = node_kind;
 		for next in rest.split(", ")
 		{
-			let j = find_or_insert(next, names, &mut num_names);
+			let j = find_or_insert(next, names, &mut num_names)
+				.ok_or_else(|| too_many(next))?;
+			if nodes[i].successors.len() == MAX_NUM_SUCCESSORS
+				|| nodes[j].predecessors.len() == MAX_NUM_SUCCESSORS
+			{
+				return Err(ParseError::at(
+					input,
+					next,
+					format!(
+						"more than {MAX_NUM_SUCCESSORS} connections per \
+						 module are not supported"
+					),
+				));
+			}
 			nodes[i].successors.push(j as u8);
-			debug_assert!(nodes[i].successors.len() <= MAX_NUM_SUCCESSORS);
 			nodes[j].predecessors.push(i as u8);
-			debug_assert!(nodes[j].predecessors.len() <= MAX_NUM_SUCCESSORS);
 		}
 	}
 
@@ -191,10 +346,6 @@ fn load_nodes<'a: 'b, 'b>(
 			{
 				memory_width += 1;
 			}
-			NodeKind::Ticker =>
-			{
-				memory_width += 1;
-			}
 			NodeKind::FlipFlop =>
 			{
 				memory_width += 1;
@@ -206,26 +357,35 @@ fn load_nodes<'a: 'b, 'b>(
 			}
 		}
 	}
-
-	if cfg!(debug_assertions)
-	{
-		dbg!(num_names);
-		for (name, node) in names[0..10].iter().zip(nodes[0..num_names].iter())
-		{
-			dbg!(name, node);
-		}
-		dbg!(memory_width);
-	}
+	Ok(())
 }
 
 const SIGNAL_MASK: u16 = 0b01111111;
 
-fn press_button(nodes: &[Node; MAX_NUM_NODES], memory: &mut u128)
-	-> [usize; 2]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pulse
+{
+	source: u8,
+	recipient: u8,
+	is_high: bool,
+}
+
+/// Sends a low pulse to the broadcaster and propagates pulses until none
+/// are left, updating `memory` and reporting every pulse sent to
+/// `on_pulse`, starting with the one from the button.
+fn press_button(
+	nodes: &[Node; MAX_NUM_NODES],
+	memory: &mut u128,
+	mut on_pulse: impl FnMut(Pulse),
+)
 {
 	let mut signals: RingBuffer<[u16; MAX_NUM_NODES]> = RingBuffer::default();
 	signals.push(1);
-	let mut nums_lo_hi = [1, 0];
+	on_pulse(Pulse {
+		source: 0,
+		recipient: 1,
+		is_high: false,
+	});
 
 	while let Some(signal) = signals.pop_head()
 	{
@@ -239,17 +399,19 @@ fn press_button(nodes: &[Node; MAX_NUM_NODES], memory: &mut u128)
 		{
 			continue;
 		};
-		nums_lo_hi[output as usize] += node.successors.len();
 		let source = i as u16;
 		let high_bits = (output as u16) << 15 | (source << 8);
 		for j in &node.successors
 		{
 			let recipient = *j as u16;
 			signals.push(high_bits | recipient);
+			on_pulse(Pulse {
+				source: i as u8,
+				recipient: *j,
+				is_high: output > 0,
+			});
 		}
 	}
-
-	nums_lo_hi
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
@@ -261,7 +423,6 @@ enum NodeKind
 	FlipFlop,
 	Conjunction,
 	Output,
-	Ticker,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -287,17 +448,6 @@ impl Node
 				let memory_bit = (*memory >> self.memory_shr) & 1;
 				Some(memory_bit as u8)
 			}
-			NodeKind::Ticker if input > 0 => None,
-			NodeKind::Ticker =>
-			{
-				*memory ^= 1 << self.memory_shr;
-				let memory_bit = (*memory >> self.memory_shr) & 1;
-				if memory_bit > 0
-				{
-					dbg!(self.memory_shr);
-				}
-				Some(memory_bit as u8)
-			}
 			NodeKind::Conjunction =>
 			{
 				let j = self
@@ -336,12 +486,87 @@ mod tests
 	#[test]
 	fn one_provided1()
 	{
-		assert_eq!(one(PROVIDED1), 32000000);
+		assert_eq!(Solution::one(PROVIDED1), 32000000);
 	}
 
 	#[test]
 	fn one_provided2()
 	{
-		assert_eq!(one(PROVIDED2), 11687500);
+		assert_eq!(Solution::one(PROVIDED2), 11687500);
+	}
+
+	#[test]
+	fn two_counters()
+	{
+		// ca sends a high pulse on every 2nd press and cb on every 4th.
+		const COUNTERS: &str = "broadcaster -> a, b\n%a -> ca\n%b -> b2\n%b2 \
+		                        -> cb\n&ca -> hub\n&cb -> hub\n&hub -> rx\n";
+		assert_eq!(Solution::two(COUNTERS), 4);
+	}
+
+	#[test]
+	fn two_unsolvable()
+	{
+		let error = |input| {
+			let network = Solution::parse(input).unwrap();
+			Solution::try_part(&network, Part::Two).unwrap_err()
+		};
+		assert_eq!(error(PROVIDED1), "no module sends pulses to 'rx'");
+		assert_eq!(
+			error("broadcaster -> a, rx\n%a -> rx\n"),
+			"input line 1 col 19: 'rx' should be fed by a single conjunction, \
+			 not 2 modules"
+		);
+		assert_eq!(
+			error("broadcaster -> a\n%a -> rx\n"),
+			"input line 1 col 16: 'a' feeds 'rx' but is not a conjunction"
+		);
+		// Nothing ever sends x a pulse, so it never sends one on to c.
+		assert_eq!(
+			error("broadcaster -> a\n%a -> b\n&x -> c\n&c -> rx\n"),
+			format!("rx gets no low pulse within {MAX_NUM_PRESSES} presses")
+		);
+		let network = Solution::parse("broadcaster -> a\n%a -> rx\n").unwrap();
+		assert_eq!(Solution::try_part(&network, Part::One), Ok(2500 * 500));
+	}
+
+	#[test]
+	fn parse_errors()
+	{
+		let error = |input| Solution::parse(input).unwrap_err().to_string();
+		assert_eq!(
+			error("broadcaster -> a\na -> b\n"),
+			"input line 2 col 1: expected '%', '&' or 'broadcaster'"
+		);
+		assert_eq!(
+			error("broadcaster -> a\n!a -> b\n"),
+			"input line 2 col 1: expected '%', '&' or 'broadcaster'"
+		);
+	}
+
+	#[test]
+	fn test_to_dot()
+	{
+		let network = Solution::parse(PROVIDED2).unwrap();
+		let dot = Solution::to_dot(&network).unwrap();
+		assert_eq!((dot.num_nodes(), dot.num_edges()), (7, 7));
+		let dot = dot.to_string();
+		assert!(dot.contains("\t\"con\" [label=\"&con\", color=\"red\"];\n"));
+		assert!(dot.contains("\t\"button\" -> \"broadcaster\";\n"));
+	}
+
+	#[test]
+	fn test_pulses()
+	{
+		let network = Solution::parse(PROVIDED1).unwrap();
+		let mut memory = 0;
+		let mut pulses = Vec::new();
+		press_button(&network.nodes, &mut memory, |pulse| pulses.push(pulse));
+		assert_eq!(pulses.len(), 12);
+		assert_eq!(pulses.iter().filter(|pulse| pulse.is_high).count(), 4);
+		let name = |i: u8| network.names[i as usize];
+		let last = pulses.last().unwrap();
+		assert_eq!((name(last.source), name(last.recipient)), ("inv", "a"));
+		assert!(last.is_high);
 	}
 }
@@ -1,175 +1,261 @@
 //!
 
-use aoc2023::run;
+use aoc2023::bit_grid::BitGrid;
+use aoc2023::geometry::Direction;
+use aoc2023::grid::Grid;
+use aoc2023::math::lcm;
+use aoc2023::parse::ParseError;
+use aoc2023::solver::{Part, Solver};
+use aoc2023::{load_input, run};
+use std::collections::HashSet;
 
-const INPUT: &str = include_str!("input.txt");
-
-const CHUNK_SIZE: usize = 64;
-const NUM_CHUNKS: usize = 3;
-const GRID_SIZE: usize = NUM_CHUNKS * CHUNK_SIZE;
+const MAX_NUM_PERIODS_SAMPLED: usize = 64;
+const MAX_NUM_STEPS_SEARCHED: usize = 10_000;
 
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> usize
-{
-	solve_one(input, 64)
-}
+pub struct Solution;
 
-fn two(input: &str) -> usize
+impl Solver for Solution
 {
-	solve_one(input, 26501365)
+	type Parsed<'a> = Garden;
+	type Answer = usize;
+
+	fn parse(input: &str) -> Result<Garden, ParseError>
+	{
+		load_walls(input)
+	}
+
+	fn part_one(garden: &Garden) -> usize
+	{
+		solve_one(garden, 64)
+	}
+
+	fn part_two(garden: &Garden) -> usize
+	{
+		count_reachable_in_tiled_garden(garden, 26501365)
+			.unwrap_or_else(|error| panic!("{error}"))
+	}
+
+	fn try_part(garden: &Garden, part: Part) -> Result<usize, String>
+	{
+		match part
+		{
+			Part::One => Ok(Self::part_one(garden)),
+			Part::Two => count_reachable_in_tiled_garden(garden, 26501365),
+		}
+	}
 }
 
-fn solve_one(input: &str, num_steps: usize) -> usize
+#[derive(Debug)]
+pub struct Garden
 {
-	let mut walls = [[0u64; NUM_CHUNKS]; GRID_SIZE];
-	let (r, c) = load_walls(&mut walls, input);
-	count_accessible(&walls, r, c, num_steps)
+	walls: BitGrid,
+	starting_row: usize,
+	starting_col: usize,
 }
 
-fn load_walls(
-	walls: &mut [[u64; NUM_CHUNKS]; GRID_SIZE],
-	input: &str,
-) -> (usize, usize)
+impl Garden
 {
-	let mut starting_row = 0;
-	let mut starting_col = 0;
-	let mut max_row = 0;
-	for (r, line) in input.lines().enumerate()
-	{
-		max_row = r;
-		let width = line.as_bytes().len();
-		for (i, chunk_xs) in line.as_bytes().chunks(CHUNK_SIZE).enumerate()
-		{
-			let mut chunk = 0u64;
-			for (d, x) in chunk_xs.iter().enumerate()
-			{
-				match *x
-				{
-					b'#' => chunk |= 1 << d,
-					b'S' =>
-					{
-						starting_row = r;
-						starting_col = i * CHUNK_SIZE + d;
-					}
-					b'.' => (),
-					_ => unreachable!(),
-				}
-			}
-			walls[r][i] = chunk;
-		}
-		set_bit(walls, r, width);
+	/// Whether there is a rock at a position of the garden tiled endlessly
+	/// in every direction, with the original at rows and columns from 0.
+	fn is_tiled_wall(&self, row: i64, col: i64) -> bool
+	{
+		let r = row.rem_euclid(self.walls.height() as i64) as usize;
+		let c = col.rem_euclid(self.walls.width() as i64) as usize;
+		self.walls.get(r, c)
 	}
+}
 
-	walls[max_row + 1] = [u64::MAX; NUM_CHUNKS];
-
-	(starting_row, starting_col)
+fn solve_one(garden: &Garden, num_steps: usize) -> usize
+{
+	count_accessible(
+		&garden.walls,
+		garden.starting_row,
+		garden.starting_col,
+		num_steps,
+	)
 }
 
-fn set_bit(grid: &mut [[u64; NUM_CHUNKS]; GRID_SIZE], r: usize, c: usize)
+fn load_walls(input: &str) -> Result<Garden, ParseError>
 {
-	grid[r][c / CHUNK_SIZE] |= 1 << (c % CHUNK_SIZE);
+	let tiles = Grid::try_parse(input, "'#', '.' or 'S'", |x| {
+		"#.S".contains(x).then_some(x)
+	})?;
+	let Some((starting_row, starting_col)) = tiles.position(|&x| x == 'S')
+	else
+	{
+		return Err(ParseError::new(1, 1, "expected a starting tile 'S'"));
+	};
+	let walls = BitGrid::from_fn(tiles.width(), tiles.height(), |r, c| {
+		tiles[r][c] == '#'
+	});
+	Ok(Garden {
+		walls,
+		starting_row,
+		starting_col,
+	})
 }
 
+/// Steps every ghost in every direction at once, keeping those that do not
+/// walk into a rock or off the garden.
 fn count_accessible(
-	walls: &[[u64; NUM_CHUNKS]; GRID_SIZE],
+	walls: &BitGrid,
 	starting_row: usize,
 	starting_col: usize,
 	num_steps: usize,
 ) -> usize
 {
-	dbg!(starting_row);
-	dbg!(starting_col);
-	dbg!(num_steps);
-
-	let mut ghosts = [[[0u64; NUM_CHUNKS]; GRID_SIZE]; 2];
-	set_bit(&mut ghosts[0], starting_row, starting_col);
-
-	let mut old = 0;
-	let mut new = 1;
-	for i in 0..num_steps
+	let mut ghosts = BitGrid::new(walls.width(), walls.height());
+	ghosts.set(starting_row, starting_col);
+	for _ in 0..num_steps
 	{
-		if cfg!(debug_assertions)
+		let mut next = ghosts.shifted(Direction::Up);
+		for direction in [Direction::Right, Direction::Down, Direction::Left]
 		{
-			dbg!(i);
-			debug_print_grid(walls, &ghosts[old], &ghosts[new]);
+			next.or(&ghosts.shifted(direction));
 		}
+		next.and_not(walls);
+		ghosts = next;
+	}
+	ghosts.count_ones()
+}
 
-		ghosts[new] = [[0u64; NUM_CHUNKS]; GRID_SIZE];
-		for r in 0..GRID_SIZE
+/// How many plots of the tiled garden are first reached after each number
+/// of steps from 0 to `max_steps`, found with a breadth-first search. A
+/// plot next to one first reached after `n` steps is first reached after
+/// `n - 1`, `n` or `n + 1` steps, so only the last two frontiers need to be
+/// kept rather than every plot reached so far.
+fn count_first_reached(garden: &Garden, max_steps: usize) -> Vec<usize>
+{
+	let start = (garden.starting_row as i64, garden.starting_col as i64);
+	let mut previous = HashSet::new();
+	let mut frontier = HashSet::from([start]);
+	let mut num_first_reached = vec![1];
+	for _ in 0..max_steps
+	{
+		let mut next = HashSet::new();
+		for &(r, c) in &frontier
 		{
-			for i in 0..NUM_CHUNKS
+			for (nr, nc) in [(r - 1, c), (r, c + 1), (r + 1, c), (r, c - 1)]
 			{
-				// dbg!(new, r, i, format!("{:064b}", ghosts[old][r][i]));
-				ghosts[new][r][i] |= ghosts[old][r][i] << 1;
-				ghosts[new][r][i] |= ghosts[old][r][i] >> 1;
-				if i > 0
-				{
-					ghosts[new][r][i] |= ghosts[old][r][i - 1] >> 63;
-				}
-				if i + 1 < NUM_CHUNKS
-				{
-					ghosts[new][r][i] |= ghosts[old][r][i + 1] << 63;
-				}
-				if r > 0
-				{
-					ghosts[new][r][i] |= ghosts[old][r - 1][i];
-				}
-				if r + 1 < GRID_SIZE
+				if !garden.is_tiled_wall(nr, nc)
+					&& !previous.contains(&(nr, nc))
+					&& !frontier.contains(&(nr, nc))
 				{
-					ghosts[new][r][i] |= ghosts[old][r + 1][i];
+					next.insert((nr, nc));
 				}
-				// dbg!(format!("{:064b}", ghosts[new][r][i]));
-				ghosts[new][r][i] &= !walls[r][i];
-				// dbg!(format!("{:064b}", ghosts[new][r][i]));
 			}
 		}
-		std::mem::swap(&mut new, &mut old);
+		num_first_reached.push(next.len());
+		previous = std::mem::replace(&mut frontier, next);
 	}
+	num_first_reached
+}
 
-	let num_ghosts: u32 = ghosts[old]
-		.iter()
-		.flat_map(|row| row.iter().map(|chunk| chunk.count_ones()))
-		.sum();
-	num_ghosts as usize
+/// How many plots can be reached in exactly each number of steps from 0 to
+/// `max_steps`: those first reached in as many steps or fewer, as long as
+/// that has the same parity, because one can always step back and forth.
+fn count_reachable(garden: &Garden, max_steps: usize) -> Vec<usize>
+{
+	let mut num_reachable = count_first_reached(garden, max_steps);
+	for n in 2..num_reachable.len()
+	{
+		num_reachable[n] += num_reachable[n - 2];
+	}
+	num_reachable
 }
 
-#[allow(unused)]
-fn debug_print_grid(
-	walls: &[[u64; NUM_CHUNKS]; GRID_SIZE],
-	ghosts: &[[u64; NUM_CHUNKS]; GRID_SIZE],
-	lingers: &[[u64; NUM_CHUNKS]; GRID_SIZE],
-)
+/// A quadratic `f(k)` known from its value and first and second finite
+/// differences at `k = start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Quadratic
 {
-	println!();
-	let mut buffer = String::new();
-	for r in 0..GRID_SIZE
+	start: usize,
+	value: i64,
+	d1: i64,
+	d2: i64,
+}
+
+impl Quadratic
+{
+	/// Looks for three equal second differences in a row, after which the
+	/// sequence is taken to be quadratic for good.
+	fn find(sequence: &[usize]) -> Option<Quadratic>
+	{
+		let f: Vec<i64> = sequence.iter().map(|&x| x as i64).collect();
+		let d2: Vec<i64> =
+			f.windows(3).map(|w| w[2] - 2 * w[1] + w[0]).collect();
+		let start = d2.windows(3).position(|w| w[0] == w[1] && w[1] == w[2])?;
+		Some(Quadratic {
+			start,
+			value: f[start],
+			d1: f[start + 1] - f[start],
+			d2: d2[start],
+		})
+	}
+
+	fn at(&self, k: usize) -> i64
 	{
-		buffer.clear();
-		for i in 0..NUM_CHUNKS
+		let x = k as i64 - self.start as i64;
+		self.value + x * self.d1 + x * (x - 1) / 2 * self.d2
+	}
+}
+
+/// The number of plots reachable in exactly `num_steps` in the garden
+/// tiled endlessly. Once the reachable area has spread over a few tiles it
+/// grows by whole tiles in a pattern that repeats every period (the width
+/// and height of the garden), so the counts after `r`, `r + period`,
+/// `r + 2 * period` and so on steps end up quadratic in the number of
+/// periods. We count directly for as many periods as it takes to see the
+/// quadratic, and extrapolate from there, giving up if that takes too many
+/// steps.
+fn count_reachable_in_tiled_garden(
+	garden: &Garden,
+	num_steps: usize,
+) -> Result<usize, String>
+{
+	let period = lcm(garden.walls.width(), garden.walls.height());
+	let rem = num_steps % period;
+	let num_periods = num_steps / period;
+	let mut num_periods_sampled = 4;
+	loop
+	{
+		let max_steps = rem + num_periods_sampled.min(num_periods) * period;
+		if max_steps > MAX_NUM_STEPS_SEARCHED
 		{
-			for j in 0..CHUNK_SIZE
-			{
-				let is_wall = walls[r][i] & (1 << j) != 0;
-				let is_ghost = ghosts[r][i] & (1 << j) != 0;
-				let symbol = match (is_wall, is_ghost)
-				{
-					(false, false) if lingers[r][i] & (1 << j) != 0 => 'o',
-					(false, false) => '.',
-					(false, true) => 'O',
-					(true, false) => '#',
-					(true, true) => '%',
-				};
-				buffer.push(symbol);
-			}
+			return Err(format!(
+				"the garden repeats every {period} steps, too many to sample \
+				 {num_periods_sampled} periods within \
+				 {MAX_NUM_STEPS_SEARCHED} steps"
+			));
 		}
-		println!("{buffer}");
+		let num_reachable = count_reachable(garden, max_steps);
+		if num_periods <= num_periods_sampled
+		{
+			return Ok(num_reachable[num_steps]);
+		}
+		let samples: Vec<usize> = num_reachable[rem..]
+			.iter()
+			.step_by(period)
+			.copied()
+			.collect();
+		if let Some(quadratic) = Quadratic::find(&samples)
+		{
+			return Ok(quadratic.at(num_periods) as usize);
+		}
+		if num_periods_sampled >= MAX_NUM_PERIODS_SAMPLED
+		{
+			return Err(format!(
+				"no quadratic growth after {MAX_NUM_PERIODS_SAMPLED} periods"
+			));
+		}
+		num_periods_sampled *= 2;
 	}
-	println!();
 }
 
 #[cfg(test)]
@@ -183,36 +269,69 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(solve_one(PROVIDED, 6), 16);
+		let garden = Solution::parse(PROVIDED).unwrap();
+		assert_eq!(solve_one(&garden, 6), 16);
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(solve_one(PROVIDED, 6), 16);
-		assert_eq!(solve_one(PROVIDED, 10), 50);
-		assert_eq!(solve_one(PROVIDED, 50), 1594);
-		assert_eq!(solve_one(PROVIDED, 100), 6536);
-		assert_eq!(solve_one(PROVIDED, 500), 167004);
-		assert_eq!(solve_one(PROVIDED, 1000), 668697);
-		assert_eq!(solve_one(PROVIDED, 5000), 16733044);
+		let garden = Solution::parse(PROVIDED).unwrap();
+		let count = |n| count_reachable_in_tiled_garden(&garden, n).unwrap();
+		assert_eq!(count(6), 16);
+		assert_eq!(count(10), 50);
+		assert_eq!(count(50), 1594);
+		assert_eq!(count(100), 6536);
+		assert_eq!(count(500), 167004);
+		assert_eq!(count(1000), 668697);
+		assert_eq!(count(5000), 16733044);
+	}
+
+	#[test]
+	fn two_brute_force()
+	{
+		let garden = Solution::parse(PROVIDED).unwrap();
+		let num_reachable = count_reachable(&garden, 300);
+		for (n, &expected) in num_reachable.iter().enumerate()
+		{
+			assert_eq!(
+				count_reachable_in_tiled_garden(&garden, n),
+				Ok(expected),
+				"{n} steps"
+			);
+		}
+	}
+
+	#[test]
+	fn two_long_period()
+	{
+		// A 13 by 1310 garden only repeats every 17030 steps.
+		let mut rows = vec![".".repeat(1310); 13];
+		rows[6].replace_range(0..1, "S");
+		let garden = Solution::parse(&rows.join("\n")).unwrap();
+		assert_eq!(
+			Solution::try_part(&garden, Part::Two),
+			Err(
+				"the garden repeats every 17030 steps, too many to sample 4 \
+				 periods within 10000 steps"
+					.to_string()
+			)
+		);
+		assert_eq!(
+			count_reachable_in_tiled_garden(&garden, 100),
+			Ok(101 * 101)
+		);
 	}
-}
 
-//
-// The normal garden fills up after a certain number of iterations, and then oscillates between "even" and "odd"
-// squares being reachable. Once I reach the same number of reachables twice, I can stop running that plot and
-// just calculate whether the final state is even or odd.
-// If I make a 5x5 grid of plots, I can update the plots one at a time (see below for 3x3) because each plot only
-// inherits from two parents and never contributes back upstream (because there are no walls on the edges).
-//
-//  5 < 1 > 6
-//  ^   ^   ^
-//  2 < S > 3
-//  v   v   v
-//  7 < 4 > 8
-//
-// Hopefully the center 3x3 of plots reach oscillation before the outer ring of cells of the 5x5 is breached,
-// so that I can collapse it into itself. Hmm but I have 16 plots in the outer ring and 8 in the middle ring.
-// Maybe I should just start outputting an 11x11 of plots of the original in order to visually see the repetition.
-//
+	#[test]
+	fn test_quadratic()
+	{
+		let squares: Vec<usize> = (0..8).map(|x| 7 + x * x).collect();
+		let quadratic = Quadratic::find(&squares).unwrap();
+		assert_eq!(quadratic.start, 0);
+		assert_eq!(quadratic.at(1000), 1000007);
+		let settling = [5, 1, 3, 8, 15, 24, 35];
+		assert_eq!(Quadratic::find(&settling).map(|x| x.start), Some(2));
+		assert_eq!(Quadratic::find(&[1, 2, 4, 8, 16, 32]), None);
+	}
+}
@@ -1,33 +1,66 @@
 //!
 
-use aoc2023::{ring_buffer::RingBuffer, run};
+use aoc2023::parse::{self, ParseError};
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 use parse_display::{Display, FromStr};
+use smallvec::SmallVec;
 use std::str::FromStr;
 
-const INPUT: &str = include_str!("input.txt");
-
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> usize
-{
-	let mut bricks: Vec<Brick> = input
-		.lines()
-		.filter(|x| !x.is_empty())
-		.map(|line| Brick::from_str(line).unwrap())
-		.collect();
-	bricks.sort_unstable();
-	drop_bricks(&mut bricks);
-	bricks.sort_unstable();
-	count_candidates(&bricks)
-}
+pub struct Solution;
 
-fn two(input: &str) -> usize
+impl Solver for Solution
 {
-	input.len() * 0
+	type Parsed<'a> = Vec<Brick>;
+	type Answer = usize;
+
+	fn parse(input: &str) -> Result<Vec<Brick>, ParseError>
+	{
+		let mut bricks: Vec<Brick> = parse::lines(input)
+			.map(|line| {
+				let brick = Brick::from_str(line).map_err(|_| {
+					ParseError::at(input, line, "expected 'x,y,z~x,y,z'")
+				})?;
+				let Brick { start, end } = brick;
+				if start.x > end.x || start.y > end.y || start.z > end.z
+				{
+					return Err(ParseError::at(
+						input,
+						line,
+						"expected the start to be the lowest corner",
+					));
+				}
+				if start.z == 0
+				{
+					return Err(ParseError::at(
+						input,
+						line,
+						"expected bricks to be above the ground at z=0",
+					));
+				}
+				Ok(brick)
+			})
+			.collect::<Result<_, _>>()?;
+		bricks.sort_unstable();
+		Ok(bricks)
+	}
+
+	fn part_one(bricks: &Vec<Brick>) -> usize
+	{
+		SupportGraph::settle(bricks).count_candidates()
+	}
+
+	fn part_two(bricks: &Vec<Brick>) -> usize
+	{
+		let graph = SupportGraph::settle(bricks);
+		(0..graph.len()).map(|i| graph.count_falling(i)).sum()
+	}
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display, FromStr)]
@@ -49,7 +82,7 @@ fn do_ranges_intersect(a0: u16, a1: u16, b0: u16, b1: u16) -> bool
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display, FromStr)]
 #[display("{start}~{end}")]
-struct Brick
+pub struct Brick
 {
 	start: Point,
 	end: Point,
@@ -57,11 +90,6 @@ struct Brick
 
 impl Brick
 {
-	fn is_pillar(&self) -> bool
-	{
-		self.start.z != self.end.z
-	}
-
 	fn overlaps(&self, other: &Brick) -> bool
 	{
 		do_ranges_intersect(
@@ -93,11 +121,6 @@ impl Brick
 
 fn drop_bricks(bricks: &mut [Brick])
 {
-	if cfg!(debug_assertions)
-	{
-		dbg!(&bricks);
-	}
-
 	for i in 0..bricks.len()
 	{
 		let (settled, floating) = bricks.split_at_mut(i);
@@ -105,114 +128,83 @@ fn drop_bricks(bricks: &mut [Brick])
 	}
 }
 
-fn count_candidates(bricks: &[Brick]) -> usize
+/// Which bricks rest on which once they have all settled. Bricks are
+/// numbered in the order they settled in, so supporters come first.
+#[derive(Debug, Clone, Default)]
+pub struct SupportGraph
 {
-	let len = bricks.len();
-
-	if cfg!(debug_assertions)
-	{
-		dbg!(&bricks);
-	}
-
-	let mut num_candidates = 0;
-	let mut only_supported_by: [usize; 128];
-	let mut pillar_indices: RingBuffer<[usize; 32]> = RingBuffer::default();
-	let mut head = 0;
-	let mut mid = 0;
-	let mut tail;
-
-	let mut floor = 1;
-	while mid < len && bricks[mid].start.z == floor
-	{
-		mid += 1;
-	}
+	/// For every brick, the bricks lying directly on top of it.
+	supports: Vec<SmallVec<[usize; 4]>>,
+	/// For every brick, the bricks it lies directly on top of.
+	supported_by: Vec<SmallVec<[usize; 4]>>,
+}
 
-	while mid < len
+impl SupportGraph
+{
+	/// Lets the bricks fall into place and records who supports whom.
+	fn settle(bricks: &[Brick]) -> SupportGraph
 	{
-		floor = bricks[mid].start.z;
-
-		debug_assert!(head < mid);
-		debug_assert!(mid <= head + 128);
-		only_supported_by = [usize::MAX; 128];
-		while let Some(pillar) =
-			pillar_indices.remove_where(|i| bricks[*i].end.z + 1 < floor)
-		{
-			if cfg!(debug_assertions)
-			{
-				dbg!("candidate", pillar);
-			}
-			num_candidates += 1;
-		}
-
-		tail = mid;
-		debug_assert!(tail <= mid + 128);
-		while tail < len && bricks[tail].start.z == floor
+		let mut bricks = bricks.to_vec();
+		drop_bricks(&mut bricks);
+		bricks.sort_unstable();
+
+		let mut graph = SupportGraph {
+			supports: vec![SmallVec::new(); bricks.len()],
+			supported_by: vec![SmallVec::new(); bricks.len()],
+		};
+		for (j, upper) in bricks.iter().enumerate()
 		{
-			let mut supporters = (head..mid)
-				.filter(|&i| bricks[i].end.z + 1 == floor)
-				.filter(|&i| bricks[tail].overlaps(&bricks[i]));
-			let main_supporter = supporters.next();
-			let pillar = pillar_indices
-				.remove_where(|&i| bricks[tail].overlaps(&bricks[i]));
-			match (main_supporter, pillar)
+			for (i, lower) in bricks[..j].iter().enumerate()
 			{
-				(Some(_support), Some(pillar)) =>
+				if lower.end.z + 1 == upper.start.z && lower.overlaps(upper)
 				{
-					if cfg!(debug_assertions)
-					{
-						dbg!("candidate", pillar);
-					}
-					num_candidates += 1;
+					graph.supports[i].push(j);
+					graph.supported_by[j].push(i);
 				}
-				(Some(support), None) =>
-				{
-					if supporters.next().is_none()
-					{
-						only_supported_by[tail - mid] = support;
-					}
-				}
-				(None, Some(_pillar)) => (),
-				(None, None) => (),
 			}
-			tail += 1;
 		}
+		graph
+	}
 
-		// dbg!(head, mid, tail, only_supported_by);
+	fn len(&self) -> usize
+	{
+		self.supports.len()
+	}
 
-		for i in head..mid
+	/// The number of bricks that can be disintegrated without any other
+	/// brick falling, because everything they support has another
+	/// supporter.
+	fn count_candidates(&self) -> usize
+	{
+		self.supports
+			.iter()
+			.filter(|above| {
+				above.iter().all(|&j| self.supported_by[j].len() > 1)
+			})
+			.count()
+	}
+
+	/// The number of other bricks that fall if `brick` is disintegrated.
+	fn count_falling(&self, brick: usize) -> usize
+	{
+		let mut num_standing: Vec<usize> =
+			self.supported_by.iter().map(|below| below.len()).collect();
+		let mut falling = vec![brick];
+		let mut num_falling = 0;
+		while let Some(i) = falling.pop()
 		{
-			let responsibility =
-				only_supported_by.iter().filter(|&&j| j == i).count();
-			if responsibility == 0
+			for &j in &self.supports[i]
 			{
-				if bricks[i].is_pillar()
+				num_standing[j] -= 1;
+				if num_standing[j] == 0
 				{
-					pillar_indices.push(i);
-				}
-				else
-				{
-					if cfg!(debug_assertions)
-					{
-						dbg!("candidate", i);
-					}
-					num_candidates += 1;
+					falling.push(j);
+					num_falling += 1;
 				}
 			}
 		}
-
-		head = mid;
-		mid = tail;
-	}
-
-	let num_on_top = mid - head;
-	if cfg!(debug_assertions)
-	{
-		dbg!(num_on_top);
-		dbg!(pillar_indices.len());
+		num_falling
 	}
-	num_candidates += num_on_top + pillar_indices.len();
-
-	num_candidates
 }
 
 #[cfg(test)]
@@ -226,7 +218,7 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 5);
+		assert_eq!(Solution::one(PROVIDED), 5);
 	}
 
 	#[test]
@@ -234,7 +226,7 @@ mod tests
 	{
 		const TOWER: &str =
 			"2,2,2~2,2,5\n1,2,10~3,2,10\n1,1,20~1,3,20\n3,1,30~3,3,30";
-		assert_eq!(one(TOWER), 2);
+		assert_eq!(Solution::one(TOWER), 2);
 	}
 
 	#[test]
@@ -242,7 +234,7 @@ mod tests
 	{
 		const RIC2B: &str =
 			"0,0,1~0,1,1\n1,1,1~1,1,1\n0,0,2~0,0,2\n0,1,2~1,1,2";
-		assert_eq!(one(RIC2B), 3);
+		assert_eq!(Solution::one(RIC2B), 3);
 	}
 
 	#[test]
@@ -250,7 +242,7 @@ mod tests
 	{
 		const KULLU00: &str =
 			"0,0,1~1,0,1\n0,1,1~0,1,2\n0,0,5~0,0,5\n0,0,4~0,1,4";
-		assert_eq!(one(KULLU00), 2);
+		assert_eq!(Solution::one(KULLU00), 2);
 	}
 
 	#[test]
@@ -258,14 +250,14 @@ mod tests
 	{
 		const BARRACUDA: &str =
 			"0,0,1~0,0,2\n1,0,1~2,0,1\n1,0,2~1,0,2\n0,0,3~1,0,3";
-		assert_eq!(one(BARRACUDA), 3);
+		assert_eq!(Solution::one(BARRACUDA), 3);
 	}
 
 	#[test]
 	fn one_reddit_falling()
 	{
 		const FALLING: &str = "2,8,48~2,8,49\n1,8,2~3,8,2";
-		assert_eq!(one(FALLING), 1);
+		assert_eq!(Solution::one(FALLING), 1);
 	}
 
 	#[test]
@@ -273,7 +265,7 @@ mod tests
 	{
 		const CRUCIFER: &str =
 			"0,0,2~0,0,4\n1,0,3~2,0,3\n1,0,4~1,0,5\n0,0,6~1,0,6";
-		assert_eq!(one(CRUCIFER), 3);
+		assert_eq!(Solution::one(CRUCIFER), 3);
 	}
 
 	#[test]
@@ -281,7 +273,7 @@ mod tests
 	{
 		const LEFTY: &str =
 			"0,0,1~0,5,1\n0,6,1~0,9,1\n0,0,2~0,0,2\n0,3,2~0,8,2";
-		assert_eq!(one(LEFTY), 3);
+		assert_eq!(Solution::one(LEFTY), 3);
 	}
 
 	#[test]
@@ -290,7 +282,7 @@ mod tests
 		const EXILE: &str = "1,0,1~1,2,1\n0,0,2~2,0,2\n0,2,3~2,2,3\n0,0,4~0,2,\
 		                     4\n2,0,5~2,2,5\n0,1,6~2,1,6\n1,1,8~1,1,9\n0,1,\
 		                     3~0,1,3";
-		assert_eq!(one(EXILE), 6);
+		assert_eq!(Solution::one(EXILE), 6);
 	}
 
 	#[test]
@@ -298,7 +290,7 @@ mod tests
 	{
 		const REV: &str = "0,0,100~9,0,100\n1,0,10~1,0,11\n2,0,20~2,0,22\n3,0,\
 		                   30~3,0,30\n0,1,150~0,1,153\n0,0,500~0,2,500";
-		assert_eq!(one(REV), 5);
+		assert_eq!(Solution::one(REV), 5);
 	}
 
 	#[test]
@@ -318,6 +310,18 @@ mod tests
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 0);
+		assert_eq!(Solution::two(PROVIDED), 7);
+	}
+
+	#[test]
+	fn test_support_graph()
+	{
+		let graph = SupportGraph::settle(&Solution::parse(PROVIDED).unwrap());
+		assert_eq!(graph.supports[0].as_slice(), &[1, 2]);
+		assert_eq!(graph.supported_by[3].as_slice(), &[1, 2]);
+		assert_eq!(graph.supported_by[6].as_slice(), &[5]);
+		let falling: Vec<usize> =
+			(0..graph.len()).map(|i| graph.count_falling(i)).collect();
+		assert_eq!(falling, vec![6, 0, 0, 0, 0, 1, 0]);
 	}
 }
@@ -1,474 +1,119 @@
 //!
 
-use aoc2023::{ring_buffer::RingBuffer, run};
-use parse_display::{Display, FromStr};
-use smallvec::SmallVec;
-
-const INPUT: &str = include_str!("input.txt");
-
-const GRID_SIZE: usize = 192;
-const MAX_NUM_VERTICES: usize = 64;
+use aoc2023::dot::Dot;
+use aoc2023::geometry::{Direction, Point};
+use aoc2023::grid::Grid;
+use aoc2023::junction_graph::JunctionGraph;
+use aoc2023::parse::ParseError;
+use aoc2023::runner;
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
-}
-
-fn one(input: &str) -> usize
-{
-	let mut grid = [[0; GRID_SIZE]; GRID_SIZE];
-	let (num_rows, num_cols) = parse_grid(&mut grid, input);
-	debug_print_grid(&grid, num_rows, num_cols);
-
-	let mut graph = Graph::default();
-	graph_grid(&mut grid, &mut graph, num_rows, num_cols);
-	debug_print_grid(&grid, num_rows, num_cols);
-	debug_print_graph(&graph);
-
-	length_of_longest_route_part_one(&graph)
-}
-
-fn two(input: &str) -> usize
-{
-	let mut grid = [[0; GRID_SIZE]; GRID_SIZE];
-	let (num_rows, num_cols) = parse_grid(&mut grid, input);
-	let mut graph = Graph::default();
-	graph_grid(&mut grid, &mut graph, num_rows, num_cols);
-	length_of_longest_route_part_two(&graph)
-}
-
-fn parse_grid(
-	grid: &mut [[u8; GRID_SIZE]; GRID_SIZE],
-	input: &str,
-) -> (usize, usize)
-{
-	let mut num_rows = 0;
-	let mut num_cols = 0;
-	for (r, line) in input.lines().enumerate()
-	{
-		let bytes = line.as_bytes();
-		num_rows = r + 1;
-		num_cols = bytes.len();
-		for (c, x) in bytes.iter().enumerate()
-		{
-			grid[r][c] = *x;
-		}
-	}
-	(num_rows, num_cols)
-}
-
-#[allow(unused)]
-fn debug_print_grid(
-	grid: &[[u8; GRID_SIZE]; GRID_SIZE],
-	num_rows: usize,
-	num_cols: usize,
-)
-{
-	if !cfg!(debug_assertions)
-	{
-		return;
-	}
-
-	println!();
-	for row in &grid[..num_rows]
-	{
-		println!("{}", std::str::from_utf8(&row[..num_cols]).unwrap());
-	}
-	println!();
-}
-
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Display, FromStr)]
-enum Direction
-{
-	#[default]
-	#[display("^")]
-	Up,
-	#[display("v")]
-	Down,
-	#[display("<")]
-	Left,
-	#[display(">")]
-	Right,
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-const ALL_DIRECTIONS: [Direction; 4] = [
-	Direction::Right,
-	Direction::Left,
-	Direction::Down,
-	Direction::Up,
-];
+pub struct Solution;
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
-struct Point
+impl Solver for Solution
 {
-	row: u16,
-	col: u16,
-}
+	type Parsed<'a> = JunctionGraph;
+	type Answer = usize;
 
-impl Point
-{
-	fn step(&mut self, direction: Direction) -> &mut Self
+	fn parse(input: &str) -> Result<JunctionGraph, ParseError>
 	{
-		match direction
+		let grid = Grid::try_parse(input, "'#', '.' or a slope", |x| {
+			"#.^>v<".contains(x).then_some(x)
+		})?;
+		let (width, height) = (grid.width(), grid.height());
+		if width < 3 || height < 3
 		{
-			Direction::Up => self.row -= 1,
-			Direction::Down => self.row += 1,
-			Direction::Left => self.col -= 1,
-			Direction::Right => self.col += 1,
+			return Err(ParseError::new(1, 1, "expected at least a 3x3 map"));
 		}
-		self
-	}
-}
-
-#[derive(Debug, Clone)]
-struct Vertex
-{
-	edges: SmallVec<[Edge; 4]>,
-	incoming: SmallVec<[Edge; 4]>,
-	point: Point,
-	color: u8,
-}
-
-#[derive(Debug, Clone, Copy, Default)]
-struct Edge
-{
-	length: u16,
-	color: u8,
-	vertex_offset: u8,
-}
-
-#[derive(Debug, Default)]
-struct Graph
-{
-	vertices: SmallVec<[Vertex; MAX_NUM_VERTICES]>,
-}
-
-#[derive(Debug, Clone, Copy, Default)]
-struct Entry
-{
-	from_vertex_offset: u8,
-	from_vertex_edge_offset: u8,
-	first_step: Point,
-}
-
-fn graph_grid(
-	grid: &mut [[u8; GRID_SIZE]; GRID_SIZE],
-	graph: &mut Graph,
-	num_rows: usize,
-	num_cols: usize,
-)
-{
-	let start = Point { row: 0, col: 1 };
-	add_vertex(grid, graph, start, b'S');
-
-	let finish_line = (num_rows - 1) as u16;
-
-	let mut queue: RingBuffer<[Entry; 128]> = RingBuffer::default();
-	graph.vertices[0].edges.push(Edge::default());
-	graph.vertices[0].edges[0].color = b'O';
-	graph.vertices[0].edges[0].length = 1;
-	queue.push(Entry {
-		from_vertex_offset: 0,
-		from_vertex_edge_offset: 0,
-		first_step: Point { row: 1, col: 1 },
-	});
-
-	let mut next_vertex_color = b'1';
-	let mut next_edge_color = b'a';
-
-	while let Some(entry) = queue.pop_head()
-	{
-		let from_offset = entry.from_vertex_offset as usize;
-		let edge_offset = entry.from_vertex_edge_offset as usize;
-		let edge_color = graph.vertices[from_offset].edges[edge_offset].color;
-		let mut cursor = entry.first_step;
-
-		'find_intersection: loop
+		if grid[0][1] != '.'
 		{
-			grid[cursor.row as usize][cursor.col as usize] = edge_color;
-
-			let direction = ALL_DIRECTIONS.into_iter().find(|&direction| {
-				let mut next = cursor;
-				next.step(direction);
-				let x = grid[next.row as usize][next.col as usize];
-				x == b'.'
-					|| (cursor != entry.first_step
-						&& [b'<', b'>', b'^', b'v'].contains(&x))
-			});
-			cursor.step(direction.unwrap());
-			graph.vertices[from_offset].edges[edge_offset].length += 1;
-
-			if cursor.row == finish_line
-			{
-				debug_assert_eq!(cursor.col as usize, num_cols - 2);
-				let to_offset = graph.vertices.len();
-				add_vertex(grid, graph, cursor, b'F');
-				finish_edge(graph, from_offset, edge_offset, to_offset);
-				return;
-			}
-			else if let Some(direction) = inspect_slope(grid, cursor)
-			{
-				cursor.step(direction);
-				graph.vertices[from_offset].edges[edge_offset].length += 1;
-				break 'find_intersection;
-			}
+			return Err(ParseError::new(1, 2, "expected the start to be '.'"));
 		}
-
-		if let Some(to_offset) = graph
-			.vertices
-			.iter()
-			.position(|vertex| vertex.point == cursor)
+		if grid[height - 1][width - 2] != '.'
 		{
-			finish_edge(graph, from_offset, edge_offset, to_offset);
-			continue;
+			return Err(ParseError::new(
+				height,
+				width - 1,
+				"expected the finish to be '.'",
+			));
 		}
 
-		let new_vertex_offset = graph.vertices.len();
-		add_vertex(grid, graph, cursor, next_vertex_color);
-		next_vertex_color += 1;
-		if next_vertex_color > b'9'
+		if let Some(col) = (0..width)
+			.find(|&col| col != width - 2 && grid[height - 1][col] != '#')
 		{
-			next_vertex_color = b'0';
+			return Err(ParseError::new(
+				height,
+				col + 1,
+				"expected only the finish to be open in the last row",
+			));
 		}
-		finish_edge(graph, from_offset, edge_offset, new_vertex_offset);
 
-		let new_directions = ALL_DIRECTIONS.into_iter().filter(|&direction| {
-			let mut next = cursor;
-			next.step(direction);
-			inspect_slope(grid, next) == Some(direction)
-		});
-		for (new_edge_offset, direction) in new_directions.enumerate()
-		{
-			let mut next = cursor;
-			next.step(direction);
-			next.step(direction);
-			graph.vertices.last_mut().unwrap().edges.push(Edge {
-				length: 2,
-				color: next_edge_color,
-				vertex_offset: 0,
-			});
-			next_edge_color += 1;
-			if next_edge_color == b'u'
-			{
-				next_edge_color = b'a';
-			}
-			queue.push(Entry {
-				from_vertex_offset: new_vertex_offset as u8,
-				from_vertex_edge_offset: new_edge_offset as u8,
-				first_step: next,
-			});
-		}
+		// The start and the finish come first and last in row-major order.
+		let terminals = [Point::new(0, 1), Point::new(height - 1, width - 2)];
+		Ok(JunctionGraph::from_grid(
+			&grid,
+			&terminals,
+			|&x| x != '#',
+			|&x, direction| {
+				x == '.' || Direction::from_arrow(x) == Some(direction)
+			},
+		))
 	}
-}
 
-fn add_vertex(
-	grid: &mut [[u8; GRID_SIZE]; GRID_SIZE],
-	graph: &mut Graph,
-	point: Point,
-	color: u8,
-)
-{
-	grid[point.row as usize][point.col as usize] = color;
-	graph.vertices.push(Vertex {
-		edges: SmallVec::default(),
-		incoming: SmallVec::default(),
-		point,
-		color,
-	});
-}
-
-fn finish_edge(
-	graph: &mut Graph,
-	from_offset: usize,
-	edge_offset: usize,
-	to_offset: usize,
-)
-{
-	let existing_edge = &mut graph.vertices[from_offset].edges[edge_offset];
-	existing_edge.vertex_offset = to_offset as u8;
-	let mut incoming = *existing_edge;
-	incoming.vertex_offset = from_offset as u8;
-	graph.vertices[to_offset].incoming.push(incoming);
-}
-
-fn inspect_slope(
-	grid: &[[u8; GRID_SIZE]; GRID_SIZE],
-	cursor: Point,
-) -> Option<Direction>
-{
-	match grid[cursor.row as usize][cursor.col as usize]
-	{
-		b'.' => None,
-		b'^' => Some(Direction::Up),
-		b'v' => Some(Direction::Down),
-		b'<' => Some(Direction::Left),
-		b'>' => Some(Direction::Right),
-		b'#' => None,
-		_ => unreachable!(),
-	}
-}
-
-#[allow(unused)]
-fn debug_print_graph(graph: &Graph)
-{
-	if !cfg!(debug_assertions)
+	fn part_one(graph: &JunctionGraph) -> usize
 	{
-		return;
+		length_of_longest_route(graph)
 	}
 
-	println!();
-	for (i, vertex) in graph.vertices.iter().enumerate()
+	fn part_two(graph: &JunctionGraph) -> usize
 	{
-		let color = char::from(vertex.color);
-		let r = vertex.point.row;
-		let c = vertex.point.col;
-		println!("{i} ('{color}') @ r{r}c{c}");
-		for edge in &vertex.edges
+		let graph = graph.to_undirected();
+		match runner::num_threads()
 		{
-			let color = char::from(edge.color);
-			let to = edge.vertex_offset;
-			let steps = edge.length;
-			println!("----{color}---> {to} ({steps} steps)");
-		}
-		for edge in &vertex.edges
-		{
-			let color = char::from(edge.color);
-			let from = edge.vertex_offset;
-			let steps = edge.length;
-			println!("<---{color}---- {from} ({steps} steps)");
+			1 => length_of_longest_route(&graph),
+			num_threads => length_of_longest_route_in_parallel(
+				&graph,
+				SPLIT_DEPTH,
+				num_threads,
+			),
 		}
 	}
-	println!();
-}
 
-fn length_of_longest_route_part_one(graph: &Graph) -> usize
-{
-	let n = graph.vertices.len();
-	let mut grid = [[0; MAX_NUM_VERTICES]; MAX_NUM_VERTICES];
-	for i in 0..n
+	fn to_dot(graph: &JunctionGraph) -> Option<Dot>
 	{
-		for edge in &graph.vertices[i].edges
-		{
-			let j = edge.vertex_offset as usize;
-			let len = edge.length as usize;
-			if len > grid[i][j]
-			{
-				grid[i][j] = len;
-			}
-			for k in 0..n
-			{
-				// i -> j -> k
-				if grid[j][k] > 0
-				{
-					if grid[i][k] < grid[i][j] + grid[j][k]
-					{
-						grid[i][k] = grid[i][j] + grid[j][k];
-					}
-				}
-				// k -> i -> j
-				if grid[k][i] > 0
-				{
-					if grid[k][j] < grid[k][i] + grid[i][j]
-					{
-						grid[k][j] = grid[k][i] + grid[i][j];
-					}
-				}
-				// h -> i -> j -> k
-				for h in 0..n
-				{
-					if grid[h][i] > 0 && grid[j][k] > 0
-					{
-						if grid[h][k] < grid[h][i] + grid[i][j] + grid[j][k]
-						{
-							grid[h][k] = grid[h][i] + grid[i][j] + grid[j][k];
-						}
-					}
-				}
-			}
-		}
+		Some(graph.to_dot("day23"))
 	}
-
-	debug_print_max_walk_grid(&grid, n);
-
-	grid[0][n - 1]
 }
 
-#[allow(unused)]
-fn debug_print_max_walk_grid(
-	grid: &[[usize; MAX_NUM_VERTICES]; MAX_NUM_VERTICES],
-	n: usize,
-)
-{
-	if !cfg!(debug_assertions)
-	{
-		return;
-	}
+/// Without slopes the search takes long enough to be worth running the
+/// routes that begin with this many trails on separate threads.
+const SPLIT_DEPTH: usize = 6;
 
-	println!();
-	for i in 0..n
-	{
-		for j in 0..n
-		{
-			print!("\t{}", grid[i][j]);
-		}
-		println!();
-	}
-	println!();
-}
-
-#[derive(Debug, Clone, Default)]
-struct Route
+fn length_of_longest_route(graph: &JunctionGraph) -> usize
 {
-	vertices: SmallVec<[u8; MAX_NUM_VERTICES]>,
-	length: usize,
+	graph
+		.longest_path(0, graph.len() - 1)
+		.expect("the finish cannot be reached")
+		.length
 }
 
-fn length_of_longest_route_part_two(graph: &Graph) -> usize
+fn length_of_longest_route_in_parallel(
+	graph: &JunctionGraph,
+	split_depth: usize,
+	num_threads: usize,
+) -> usize
 {
-	let n = graph.vertices.len();
-	let finish = (n - 1) as u8;
-	let mut answer = 0;
-	let mut stack: SmallVec<[Route; 1024]> = SmallVec::new();
-	stack.push(Route::default());
-	stack[0].vertices.push(0);
-	'withstack: while let Some(route) = stack.pop()
-	{
-		let i = *route.vertices.last().unwrap() as usize;
-		for edge in &graph.vertices[i].edges
-		{
-			if edge.vertex_offset == finish
-			{
-				let total_length = route.length + edge.length as usize;
-				if total_length > answer
-				{
-					answer = total_length;
-				}
-			}
-			else if route.vertices.len() + 1 == n
-			{
-				continue 'withstack;
-			}
-			else if !route.vertices.contains(&edge.vertex_offset)
-			{
-				let mut next = route.clone();
-				next.vertices.push(edge.vertex_offset);
-				next.length += edge.length as usize;
-				stack.push(next);
-			}
-		}
-		for edge in &graph.vertices[i].incoming
-		{
-			if !route.vertices.contains(&edge.vertex_offset)
-			{
-				let mut next = route.clone();
-				next.vertices.push(edge.vertex_offset);
-				next.length += edge.length as usize;
-				stack.push(next);
-			}
-		}
-	}
-	answer
+	graph
+		.longest_path_parallel(0, graph.len() - 1, split_depth, num_threads)
+		.expect("the finish cannot be reached")
+		.length
 }
 
 #[cfg(test)]
@@ -482,12 +127,60 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 94);
+		assert_eq!(Solution::one(PROVIDED), 94);
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 154);
+		assert_eq!(Solution::two(PROVIDED), 154);
+	}
+
+	#[test]
+	fn parallel_agrees()
+	{
+		let graph = Solution::parse(PROVIDED).unwrap();
+		for graph in [graph.to_undirected(), graph]
+		{
+			let finish = graph.len() - 1;
+			let expected = graph.longest_path(0, finish);
+			for split_depth in 0..=SPLIT_DEPTH
+			{
+				assert_eq!(
+					graph.longest_path_parallel(0, finish, split_depth, 4),
+					expected
+				);
+				assert_eq!(
+					length_of_longest_route_in_parallel(&graph, split_depth, 2),
+					length_of_longest_route(&graph)
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn parse_errors()
+	{
+		let error = |input| Solution::parse(input).unwrap_err().to_string();
+		assert_eq!(
+			error("#.#\n#.#\n#..\n"),
+			"input line 3 col 3: expected only the finish to be open in the \
+			 last row"
+		);
+		assert_eq!(
+			error("##.\n#.#\n#.#\n"),
+			"input line 1 col 2: expected the start to be '.'"
+		);
+	}
+
+	#[test]
+	fn test_to_dot()
+	{
+		let graph = Solution::parse(PROVIDED).unwrap();
+		let dot = Solution::to_dot(&graph).unwrap();
+		assert_eq!((dot.num_nodes(), dot.num_edges()), (9, 12));
+		assert!(dot
+			.to_string()
+			.contains("\t\"0\" [label=\"r0c1\"];\n\t\"1\" [label=\"r3c11\"];"));
 	}
 }
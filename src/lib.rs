@@ -2,41 +2,62 @@
 
 mod lib
 {
+	pub mod answers;
+	pub mod bench;
 	pub mod binary_search_range;
+	pub mod bit_grid;
+	pub mod cycle;
+	pub mod dot;
+	pub mod geometry;
+	pub mod grid;
+	pub mod input;
+	pub mod junction_graph;
+	pub mod math;
+	pub mod parse;
 	pub mod ring_buffer;
+	pub mod runner;
+	pub mod runtime_log;
+	pub mod scaffold;
+	pub mod search;
+	pub mod solver;
 }
 pub use binary_search_range::*;
 pub use lib::*;
 
 #[macro_export]
 macro_rules! run {
-	($f:ident($x:expr)) => {
-		let start = std::time::Instant::now();
-		let output = $f($x);
-		let runtime_in_ms = start.elapsed().as_secs_f64() * 1000.0;
-		let path = std::path::Path::new(std::file!());
-		let binname = path
-			.parent()
-			.unwrap()
-			.file_name()
-			.unwrap()
-			.to_str()
-			.unwrap();
-		let partname = stringify!($f);
-		println!(
-			"### {binname} part {partname}: {output} ### (took \
-			 {runtime_in_ms:.1} ms)"
+	($solver:ty, $input:expr) => {
+		let day = $crate::input::day_from_source_path(std::file!());
+		let source = $crate::input::InputSource::from_arg(
+			std::env::args().nth(1).as_deref(),
+			day,
 		);
-
-		if cfg!(not(debug_assertions))
+		let options = $crate::runner::RunOptions::from_env(day, &source);
+		let mut is_success = true;
+		for part in $crate::solver::Part::BOTH
+		{
+			let result = $crate::runner::run_solver::<$solver>(
+				day, $input, part, &options,
+			);
+			match result
+			{
+				Ok(_) => (),
+				Err(error @ $crate::runner::RunError::Parse(_)) =>
+				{
+					eprintln!("{day} {error}");
+					is_success = false;
+					break;
+				}
+				Err(error) =>
+				{
+					eprintln!("{day} part {part}: {error}");
+					is_success = false;
+				}
+			}
+		}
+		if !is_success
 		{
-			use std::io::Write;
-			let mut log = std::fs::OpenOptions::new()
-				.append(true)
-				.open("meta/runtime.log.txt")
-				.unwrap();
-			write!(log, "{binname} part {partname}: {runtime_in_ms:.1} ms\n")
-				.unwrap();
+			std::process::exit(1);
 		}
 	};
 }
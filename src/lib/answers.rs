@@ -0,0 +1,268 @@
+/**/
+
+use crate::solver::Part;
+use std::path::{Path, PathBuf};
+
+/// Known-good answers for a day's real input, kept in `answers.toml` next to
+/// its `input.txt`:
+///
+/// ```toml
+/// one = 288
+/// two = "71503"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers
+{
+	pub one: Option<String>,
+	pub two: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict
+{
+	Pass,
+	Fail
+	{
+		expected: String,
+	},
+	Unknown,
+	/// The answer was stored as the new known-good one.
+	Recorded,
+}
+
+impl std::fmt::Display for Verdict
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		match self
+		{
+			Verdict::Pass => write!(f, "PASS"),
+			Verdict::Fail { expected } =>
+			{
+				write!(f, "FAIL, expected {expected}")
+			}
+			Verdict::Unknown => write!(f, "UNKNOWN"),
+			Verdict::Recorded => write!(f, "RECORDED"),
+		}
+	}
+}
+
+impl Answers
+{
+	/// Reads the file, treating a missing file as having no answers.
+	pub fn read(path: &Path) -> Result<Answers, String>
+	{
+		match std::fs::read_to_string(path)
+		{
+			Ok(contents) => Answers::parse(&contents)
+				.map_err(|error| format!("{}: {error}", path.display())),
+			Err(error) if error.kind() == std::io::ErrorKind::NotFound =>
+			{
+				Ok(Answers::default())
+			}
+			Err(error) => Err(format!("{}: {error}", path.display())),
+		}
+	}
+
+	pub fn write(&self, path: &Path) -> std::io::Result<()>
+	{
+		std::fs::write(path, self.to_toml())
+	}
+
+	pub fn parse(contents: &str) -> Result<Answers, String>
+	{
+		let mut answers = Answers::default();
+		for (i, line) in contents.lines().enumerate()
+		{
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#')
+			{
+				continue;
+			}
+			let error = |message: &str| format!("line {}: {message}", i + 1);
+			let (key, value) = line
+				.split_once('=')
+				.ok_or_else(|| error("expected 'key = value'"))?;
+			let value = value.trim();
+			let value = match value.strip_prefix('"')
+			{
+				Some(quoted) => unescape(quoted).map_err(error)?,
+				None => value.to_string(),
+			};
+			let part = key.trim().parse().map_err(|_| {
+				error(&format!(
+					"unknown key '{}', expected one or two",
+					key.trim()
+				))
+			})?;
+			*answers.get_mut(part) = Some(value);
+		}
+		Ok(answers)
+	}
+
+	pub fn to_toml(&self) -> String
+	{
+		let mut toml = String::new();
+		for part in Part::BOTH
+		{
+			if let Some(answer) = self.get(part)
+			{
+				if !answer.is_empty() && answer.parse::<i64>().is_ok()
+				{
+					toml.push_str(&format!("{part} = {answer}\n"));
+				}
+				else
+				{
+					toml.push_str(&format!(
+						"{part} = \"{}\"\n",
+						escape(answer)
+					));
+				}
+			}
+		}
+		toml
+	}
+
+	pub fn get(&self, part: Part) -> Option<&str>
+	{
+		match part
+		{
+			Part::One => self.one.as_deref(),
+			Part::Two => self.two.as_deref(),
+		}
+	}
+
+	fn get_mut(&mut self, part: Part) -> &mut Option<String>
+	{
+		match part
+		{
+			Part::One => &mut self.one,
+			Part::Two => &mut self.two,
+		}
+	}
+
+	pub fn set(&mut self, part: Part, answer: String)
+	{
+		*self.get_mut(part) = Some(answer);
+	}
+
+	pub fn check(&self, part: Part, answer: &str) -> Verdict
+	{
+		match self.get(part)
+		{
+			Some(expected) if expected == answer => Verdict::Pass,
+			Some(expected) => Verdict::Fail {
+				expected: expected.to_string(),
+			},
+			None => Verdict::Unknown,
+		}
+	}
+}
+
+/// Quotes and backslashes are escaped with a backslash, and newlines are
+/// written as `\n`, as in TOML's basic strings.
+fn escape(answer: &str) -> String
+{
+	let mut escaped = String::with_capacity(answer.len());
+	for x in answer.chars()
+	{
+		match x
+		{
+			'"' => escaped.push_str("\\\""),
+			'\\' => escaped.push_str("\\\\"),
+			'\n' => escaped.push_str("\\n"),
+			_ => escaped.push(x),
+		}
+	}
+	escaped
+}
+
+/// Undoes `escape` on what follows the opening quote of a string, which
+/// must end with the closing quote.
+fn unescape(quoted: &str) -> Result<String, &'static str>
+{
+	let mut value = String::with_capacity(quoted.len());
+	let mut chars = quoted.chars();
+	loop
+	{
+		match chars.next().ok_or("unterminated string")?
+		{
+			'"' if chars.as_str().is_empty() => return Ok(value),
+			'"' => return Err("unexpected text after string"),
+			'\\' => match chars.next()
+			{
+				Some('"') => value.push('"'),
+				Some('\\') => value.push('\\'),
+				Some('n') => value.push('\n'),
+				_ => return Err("expected '\\\"', '\\\\' or '\\n' after '\\'"),
+			},
+			x => value.push(x),
+		}
+	}
+}
+
+/// The `answers.toml` next to the day's `input.txt`.
+pub fn default_path(day: &str) -> PathBuf
+{
+	crate::input::default_path(day).with_file_name("answers.toml")
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_roundtrip()
+	{
+		let answers =
+			Answers::parse("# day06\none = 288\ntwo = \"abc\"\n").unwrap();
+		assert_eq!(answers.get(Part::One), Some("288"));
+		assert_eq!(answers.get(Part::Two), Some("abc"));
+		assert_eq!(answers.to_toml(), "one = 288\ntwo = \"abc\"\n");
+		assert_eq!(Answers::parse(&answers.to_toml()), Ok(answers));
+		assert!(Answers::parse("three = 3").is_err());
+		assert!(Answers::parse("one = \"3").is_err());
+	}
+
+	#[test]
+	fn test_roundtrip_escaped()
+	{
+		let mut answers = Answers::default();
+		answers.set(Part::One, "a \"b\" \\c".to_string());
+		answers.set(Part::Two, "#.\n.#".to_string());
+		let toml = answers.to_toml();
+		assert_eq!(toml, "one = \"a \\\"b\\\" \\\\c\"\ntwo = \"#.\\n.#\"\n");
+		assert_eq!(Answers::parse(&toml), Ok(answers));
+		assert_eq!(
+			Answers::parse("one = \"a\\qb\""),
+			Err("line 1: expected '\\\"', '\\\\' or '\\n' after '\\'"
+				.to_string())
+		);
+		assert_eq!(
+			Answers::parse("one = \"a\"b\""),
+			Err("line 1: unexpected text after string".to_string())
+		);
+		assert_eq!(
+			Answers::parse("one = \"a\\\""),
+			Err("line 1: unterminated string".to_string())
+		);
+	}
+
+	#[test]
+	fn test_check()
+	{
+		let mut answers = Answers::default();
+		answers.set(Part::One, "42".to_string());
+		assert_eq!(answers.check(Part::One, "42"), Verdict::Pass);
+		assert_eq!(
+			answers.check(Part::One, "41"),
+			Verdict::Fail {
+				expected: "42".to_string()
+			}
+		);
+		assert_eq!(answers.check(Part::Two, "42"), Verdict::Unknown);
+		assert!(default_path("day01").ends_with("src/bin/day01/answers.toml"));
+	}
+}
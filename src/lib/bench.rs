@@ -0,0 +1,221 @@
+/**/
+
+use std::time::{Duration, Instant};
+
+/// How often to run a part when benchmarking. Runs continue until
+/// `max_runs` is reached or `time_budget` is spent, but never fewer than
+/// `min_runs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchConfig
+{
+	pub warmup_runs: usize,
+	pub min_runs: usize,
+	pub max_runs: usize,
+	pub time_budget: Duration,
+}
+
+impl Default for BenchConfig
+{
+	fn default() -> BenchConfig
+	{
+		BenchConfig {
+			warmup_runs: 3,
+			min_runs: 10,
+			max_runs: 1000,
+			time_budget: Duration::from_secs(2),
+		}
+	}
+}
+
+impl BenchConfig
+{
+	/// Exactly `num_runs` measured runs, however long they take.
+	pub fn with_runs(num_runs: usize) -> BenchConfig
+	{
+		BenchConfig {
+			min_runs: num_runs,
+			max_runs: num_runs,
+			..BenchConfig::default()
+		}
+	}
+
+	/// Benchmarking is on if `AOC_BENCH` is set to anything other than `0`;
+	/// `AOC_BENCH_RUNS` fixes the number of measured runs.
+	pub fn from_env() -> Option<BenchConfig>
+	{
+		match std::env::var("AOC_BENCH")
+		{
+			Ok(value) if value != "0" && !value.is_empty() =>
+			{
+				let num_runs = std::env::var("AOC_BENCH_RUNS")
+					.ok()
+					.and_then(|x| x.parse().ok())
+					.filter(|&x| x > 0);
+				Some(num_runs.map_or_else(BenchConfig::default, |x| {
+					BenchConfig::with_runs(x)
+				}))
+			}
+			_ => None,
+		}
+	}
+}
+
+/// Runtimes in microseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats
+{
+	pub num_runs: usize,
+	pub min: f64,
+	pub median: f64,
+	pub mean: f64,
+	pub p95: f64,
+}
+
+impl Stats
+{
+	pub fn from_runtimes(runtimes: &[Duration]) -> Stats
+	{
+		assert!(!runtimes.is_empty());
+		let mut micros: Vec<f64> =
+			runtimes.iter().map(|x| x.as_secs_f64() * 1e6).collect();
+		micros.sort_unstable_by(f64::total_cmp);
+		let n = micros.len();
+		let median = if n.is_multiple_of(2)
+		{
+			(micros[n / 2 - 1] + micros[n / 2]) / 2.0
+		}
+		else
+		{
+			micros[n / 2]
+		};
+		let p95_rank = (n * 95).div_ceil(100);
+		Stats {
+			num_runs: n,
+			min: micros[0],
+			median,
+			mean: micros.iter().sum::<f64>() / n as f64,
+			p95: micros[p95_rank - 1],
+		}
+	}
+}
+
+impl std::fmt::Display for Stats
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		write!(
+			f,
+			"min {:.1} µs, median {:.1} µs, mean {:.1} µs, p95 {:.1} µs over \
+			 {} runs",
+			self.min, self.median, self.mean, self.p95, self.num_runs
+		)
+	}
+}
+
+/// A run produced a different answer than the first one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nondeterministic
+{
+	pub expected: String,
+	pub actual: String,
+	pub run: usize,
+}
+
+impl std::fmt::Display for Nondeterministic
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		write!(
+			f,
+			"answer changed from {} to {} on run {}",
+			self.expected, self.actual, self.run
+		)
+	}
+}
+
+impl std::error::Error for Nondeterministic {}
+
+pub fn benchmark<T: std::fmt::Display + PartialEq>(
+	config: &BenchConfig,
+	mut solve: impl FnMut() -> T,
+) -> Result<(T, Stats), Nondeterministic>
+{
+	let answer = solve();
+	let check = |output: T, run: usize| {
+		if output == answer
+		{
+			Ok(())
+		}
+		else
+		{
+			Err(Nondeterministic {
+				expected: answer.to_string(),
+				actual: output.to_string(),
+				run,
+			})
+		}
+	};
+
+	for run in 1..=config.warmup_runs
+	{
+		check(solve(), run)?;
+	}
+
+	let mut runtimes = Vec::with_capacity(config.min_runs);
+	let budget_start = Instant::now();
+	while runtimes.len() < config.max_runs
+		&& (runtimes.len() < config.min_runs
+			|| budget_start.elapsed() < config.time_budget)
+	{
+		let start = Instant::now();
+		let output = solve();
+		runtimes.push(start.elapsed());
+		check(output, config.warmup_runs + runtimes.len())?;
+	}
+
+	Ok((answer, Stats::from_runtimes(&runtimes)))
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_stats()
+	{
+		let runtimes: Vec<Duration> =
+			(1..=20).rev().map(Duration::from_micros).collect();
+		let stats = Stats::from_runtimes(&runtimes);
+		assert_eq!(stats.num_runs, 20);
+		assert_eq!(stats.min, 1.0);
+		assert_eq!(stats.median, 10.5);
+		assert_eq!(stats.mean, 10.5);
+		assert_eq!(stats.p95, 19.0);
+	}
+
+	#[test]
+	fn test_benchmark()
+	{
+		let config = BenchConfig::with_runs(5);
+		let (answer, stats) = benchmark(&config, || 42).unwrap();
+		assert_eq!(answer, 42);
+		assert_eq!(stats.num_runs, 5);
+
+		let mut counter = 0;
+		let error = benchmark(&config, || {
+			counter += 1;
+			counter / 3
+		})
+		.unwrap_err();
+		assert_eq!(
+			error,
+			Nondeterministic {
+				expected: "0".to_string(),
+				actual: "1".to_string(),
+				run: 2,
+			}
+		);
+	}
+}
@@ -0,0 +1,363 @@
+/**/
+
+use crate::geometry::Direction;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A grid of bits, each row packed into 64-bit words so that whole rows
+/// can be shifted and combined at once. Bits past the width of a row are
+/// always zero, so that grids with the same bits set compare and hash the
+/// same.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid
+{
+	width: usize,
+	height: usize,
+	words_per_row: usize,
+	words: Vec<u64>,
+}
+
+impl BitGrid
+{
+	pub fn new(width: usize, height: usize) -> BitGrid
+	{
+		let words_per_row = width.div_ceil(WORD_BITS);
+		BitGrid {
+			width,
+			height,
+			words_per_row,
+			words: vec![0; words_per_row * height],
+		}
+	}
+
+	pub fn from_fn(
+		width: usize,
+		height: usize,
+		mut f: impl FnMut(usize, usize) -> bool,
+	) -> BitGrid
+	{
+		let mut grid = BitGrid::new(width, height);
+		for r in 0..height
+		{
+			for c in 0..width
+			{
+				if f(r, c)
+				{
+					grid.set(r, c);
+				}
+			}
+		}
+		grid
+	}
+
+	pub fn width(&self) -> usize
+	{
+		self.width
+	}
+
+	pub fn height(&self) -> usize
+	{
+		self.height
+	}
+
+	pub fn get(&self, row: usize, col: usize) -> bool
+	{
+		debug_assert!(row < self.height && col < self.width);
+		let word = self.words[row * self.words_per_row + col / WORD_BITS];
+		word & (1 << (col % WORD_BITS)) != 0
+	}
+
+	pub fn set(&mut self, row: usize, col: usize)
+	{
+		debug_assert!(row < self.height && col < self.width);
+		self.words[row * self.words_per_row + col / WORD_BITS] |=
+			1 << (col % WORD_BITS);
+	}
+
+	pub fn clear(&mut self, row: usize, col: usize)
+	{
+		debug_assert!(row < self.height && col < self.width);
+		self.words[row * self.words_per_row + col / WORD_BITS] &=
+			!(1 << (col % WORD_BITS));
+	}
+
+	pub fn is_empty(&self) -> bool
+	{
+		self.words.iter().all(|&word| word == 0)
+	}
+
+	pub fn count_ones(&self) -> usize
+	{
+		self.words
+			.iter()
+			.map(|word| word.count_ones() as usize)
+			.sum()
+	}
+
+	pub fn count_ones_in_row(&self, row: usize) -> usize
+	{
+		self.row(row)
+			.iter()
+			.map(|word| word.count_ones() as usize)
+			.sum()
+	}
+
+	/// The positions of the bits that are set, row by row.
+	pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> + '_
+	{
+		(0..self.height).flat_map(move |r| {
+			self.row(r).iter().enumerate().flat_map(move |(i, &word)| {
+				let mut word = word;
+				std::iter::from_fn(move || {
+					(word != 0).then(|| {
+						let bit = word.trailing_zeros() as usize;
+						word &= word - 1;
+						(r, i * WORD_BITS + bit)
+					})
+				})
+			})
+		})
+	}
+
+	pub fn and(&mut self, other: &BitGrid)
+	{
+		self.combine(other, |a, b| a & b);
+	}
+
+	pub fn or(&mut self, other: &BitGrid)
+	{
+		self.combine(other, |a, b| a | b);
+	}
+
+	/// Clears the bits that are set in `other`.
+	pub fn and_not(&mut self, other: &BitGrid)
+	{
+		self.combine(other, |a, b| a & !b);
+	}
+
+	fn combine(&mut self, other: &BitGrid, f: impl Fn(u64, u64) -> u64)
+	{
+		assert_eq!((self.width, self.height), (other.width, other.height));
+		for (a, &b) in self.words.iter_mut().zip(&other.words)
+		{
+			*a = f(*a, b);
+		}
+	}
+
+	/// Moves every bit one cell towards `direction`, dropping the ones that
+	/// fall off the edge.
+	pub fn shifted(&self, direction: Direction) -> BitGrid
+	{
+		let n = self.words_per_row;
+		let mut shifted = BitGrid::new(self.width, self.height);
+		match direction
+		{
+			Direction::Up if self.height > 0 =>
+			{
+				shifted.words[..n * (self.height - 1)]
+					.copy_from_slice(&self.words[n..]);
+			}
+			Direction::Down if self.height > 0 =>
+			{
+				shifted.words[n..]
+					.copy_from_slice(&self.words[..n * (self.height - 1)]);
+			}
+			Direction::Up | Direction::Down => (),
+			Direction::Right =>
+			{
+				for r in 0..self.height
+				{
+					let from = self.row(r);
+					let to = shifted.row_mut(r);
+					let mut carry = 0;
+					for i in 0..n
+					{
+						to[i] = (from[i] << 1) | carry;
+						carry = from[i] >> (WORD_BITS - 1);
+					}
+				}
+				shifted.clear_padding();
+			}
+			Direction::Left =>
+			{
+				for r in 0..self.height
+				{
+					let from = self.row(r);
+					let to = shifted.row_mut(r);
+					let mut carry = 0;
+					for i in (0..n).rev()
+					{
+						to[i] = (from[i] >> 1) | carry;
+						carry = from[i] << (WORD_BITS - 1);
+					}
+				}
+			}
+		}
+		shifted
+	}
+
+	/// Flips the grid over its main diagonal, so that rows become columns.
+	pub fn transposed(&self) -> BitGrid
+	{
+		let mut transposed = BitGrid::new(self.height, self.width);
+		for (r, c) in self.positions()
+		{
+			transposed.set(c, r);
+		}
+		transposed
+	}
+
+	/// Turns the grid a quarter turn clockwise, so that the first column
+	/// becomes the first row (read from the bottom up).
+	pub fn rotated_clockwise(&self) -> BitGrid
+	{
+		let mut rotated = BitGrid::new(self.height, self.width);
+		for (r, c) in self.positions()
+		{
+			rotated.set(c, self.height - 1 - r);
+		}
+		rotated
+	}
+
+	fn row(&self, row: usize) -> &[u64]
+	{
+		let start = row * self.words_per_row;
+		&self.words[start..start + self.words_per_row]
+	}
+
+	fn row_mut(&mut self, row: usize) -> &mut [u64]
+	{
+		let start = row * self.words_per_row;
+		&mut self.words[start..start + self.words_per_row]
+	}
+
+	fn clear_padding(&mut self)
+	{
+		let num_used_bits = self.width % WORD_BITS;
+		if num_used_bits == 0
+		{
+			return;
+		}
+		let mask = (1 << num_used_bits) - 1;
+		for r in 0..self.height
+		{
+			*self.row_mut(r).last_mut().unwrap() &= mask;
+		}
+	}
+}
+
+impl std::fmt::Display for BitGrid
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		for r in 0..self.height
+		{
+			for c in 0..self.width
+			{
+				write!(f, "{}", if self.get(r, c) { '#' } else { '.' })?;
+			}
+			writeln!(f)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	fn parse(input: &str) -> BitGrid
+	{
+		let lines: Vec<&[u8]> = input.lines().map(|x| x.as_bytes()).collect();
+		BitGrid::from_fn(lines[0].len(), lines.len(), |r, c| {
+			lines[r][c] == b'#'
+		})
+	}
+
+	#[test]
+	fn test_shifted()
+	{
+		let grid = parse("#..#\n.#..\n...#\n");
+		assert_eq!(
+			grid.shifted(Direction::Up).to_string(),
+			".#..\n...#\n....\n"
+		);
+		assert_eq!(
+			grid.shifted(Direction::Down).to_string(),
+			"....\n#..#\n.#..\n"
+		);
+		assert_eq!(
+			grid.shifted(Direction::Right).to_string(),
+			".#..\n..#.\n....\n"
+		);
+		assert_eq!(
+			grid.shifted(Direction::Left).to_string(),
+			"..#.\n#...\n..#.\n"
+		);
+	}
+
+	#[test]
+	fn test_shifted_across_words()
+	{
+		let width = 150;
+		let mut grid = BitGrid::new(width, 2);
+		for c in [0, 63, 64, 127, 128, 149]
+		{
+			grid.set(1, c);
+		}
+		let right = grid.shifted(Direction::Right);
+		assert_eq!(
+			right.positions().collect::<Vec<_>>(),
+			vec![(1, 1), (1, 64), (1, 65), (1, 128), (1, 129)]
+		);
+		let left = grid.shifted(Direction::Left);
+		assert_eq!(
+			left.positions().collect::<Vec<_>>(),
+			vec![(1, 62), (1, 63), (1, 126), (1, 127), (1, 148)]
+		);
+		// The bit shifted past the edge must not linger as padding.
+		let mut expected = BitGrid::new(width, 2);
+		for (r, c) in right.positions()
+		{
+			expected.set(r, c);
+		}
+		assert_eq!(right, expected);
+		assert_eq!(right.count_ones(), 5);
+		assert_eq!(right.count_ones_in_row(0), 0);
+	}
+
+	#[test]
+	fn test_combine()
+	{
+		let a = parse("##..\n");
+		let b = parse(".##.\n");
+		let mut x = a.clone();
+		x.and(&b);
+		assert_eq!(x.to_string(), ".#..\n");
+		let mut x = a.clone();
+		x.or(&b);
+		assert_eq!(x.to_string(), "###.\n");
+		let mut x = a.clone();
+		x.and_not(&b);
+		assert_eq!(x.to_string(), "#...\n");
+		x.clear(0, 0);
+		assert!(x.is_empty());
+	}
+
+	#[test]
+	fn test_transposed_and_rotated()
+	{
+		let grid = parse("##.\n..#\n");
+		assert_eq!(grid.transposed().to_string(), "#.\n#.\n.#\n");
+		assert_eq!(grid.rotated_clockwise().to_string(), ".#\n.#\n#.\n");
+		let mut rotated = grid.clone();
+		for _ in 0..4
+		{
+			rotated = rotated.rotated_clockwise();
+		}
+		assert_eq!(rotated, grid);
+		assert_eq!(grid.transposed().transposed(), grid);
+	}
+}
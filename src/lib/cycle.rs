@@ -0,0 +1,187 @@
+/**/
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Stepping from a state over and over in a finite state space must end up
+/// going around in a loop: after a tail of `tail_len` steps, the states
+/// repeat every `period` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle
+{
+	pub tail_len: usize,
+	pub period: usize,
+}
+
+impl Cycle
+{
+	/// Finds the cycle with Brent's algorithm, which keeps only two states
+	/// around but steps a few times more than there are distinct states.
+	pub fn brent<S: Clone + Eq>(
+		start: &S,
+		mut step: impl FnMut(&S) -> S,
+	) -> Cycle
+	{
+		// Find the period by letting the hare run ahead of the tortoise,
+		// teleporting the tortoise to the hare at every power of two.
+		let mut power = 1;
+		let mut period = 1;
+		let mut tortoise = start.clone();
+		let mut hare = step(start);
+		while tortoise != hare
+		{
+			if power == period
+			{
+				tortoise = hare.clone();
+				power *= 2;
+				period = 0;
+			}
+			hare = step(&hare);
+			period += 1;
+		}
+
+		// With the hare one period ahead, they meet where the loop starts.
+		let mut tortoise = start.clone();
+		let mut hare = start.clone();
+		for _ in 0..period
+		{
+			hare = step(&hare);
+		}
+		let mut tail_len = 0;
+		while tortoise != hare
+		{
+			tortoise = step(&tortoise);
+			hare = step(&hare);
+			tail_len += 1;
+		}
+		Cycle { tail_len, period }
+	}
+
+	/// Finds the cycle by remembering every state seen, which steps the
+	/// fewest times possible and keeps the states for `History::state_at`.
+	pub fn with_hash_map<S: Clone + Eq + Hash>(
+		start: S,
+		mut step: impl FnMut(&S) -> S,
+	) -> History<S>
+	{
+		let mut seen: HashMap<S, usize> = HashMap::new();
+		let mut states = Vec::new();
+		let mut state = start;
+		loop
+		{
+			if let Some(&tail_len) = seen.get(&state)
+			{
+				let cycle = Cycle {
+					tail_len,
+					period: states.len() - tail_len,
+				};
+				return History { cycle, states };
+			}
+			let next = step(&state);
+			seen.insert(state.clone(), states.len());
+			states.push(state);
+			state = next;
+		}
+	}
+
+	/// The earliest step after which the state is the same as after `n`.
+	pub fn reduce(&self, n: usize) -> usize
+	{
+		if n < self.tail_len
+		{
+			n
+		}
+		else
+		{
+			self.tail_len + (n - self.tail_len) % self.period
+		}
+	}
+
+	/// The state after `n` steps from `start`, which takes fewer than
+	/// `tail_len + period` steps however large `n` is.
+	pub fn state_at<S>(
+		&self,
+		start: S,
+		mut step: impl FnMut(&S) -> S,
+		n: usize,
+	) -> S
+	{
+		(0..self.reduce(n)).fold(start, |state, _| step(&state))
+	}
+}
+
+/// A cycle along with every state up to the one where it loops back.
+#[derive(Debug, Clone)]
+pub struct History<S>
+{
+	pub cycle: Cycle,
+	states: Vec<S>,
+}
+
+impl<S> History<S>
+{
+	/// The state after `n` steps from the start.
+	pub fn state_at(&self, n: usize) -> &S
+	{
+		&self.states[self.cycle.reduce(n)]
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	/// 0, 1, 2, 3, 4, 5, 6, 7, 3, 4, ...
+	fn rho(x: &u32) -> u32
+	{
+		if *x == 7
+		{
+			3
+		}
+		else
+		{
+			x + 1
+		}
+	}
+
+	#[test]
+	fn test_brent()
+	{
+		let expected = Cycle {
+			tail_len: 3,
+			period: 5,
+		};
+		assert_eq!(Cycle::brent(&0, rho), expected);
+		assert_eq!(
+			Cycle::brent(&5, rho),
+			Cycle {
+				tail_len: 0,
+				period: 5,
+			}
+		);
+		assert_eq!(
+			Cycle::brent(&0, |x| *x),
+			Cycle {
+				tail_len: 0,
+				period: 1,
+			}
+		);
+		assert_eq!(expected.state_at(0, rho, 2), 2);
+		assert_eq!(expected.state_at(0, rho, 1_000_000_006), 6);
+	}
+
+	#[test]
+	fn test_with_hash_map()
+	{
+		let history = Cycle::with_hash_map(0, rho);
+		assert_eq!(history.cycle, Cycle::brent(&0, rho));
+		for n in 0..100
+		{
+			let expected = (0..n).fold(0, |x, _| rho(&x));
+			assert_eq!(*history.state_at(n), expected);
+			assert_eq!(history.cycle.state_at(0, rho, n), expected);
+		}
+	}
+}
@@ -0,0 +1,204 @@
+/**/
+
+use std::fmt::Write as _;
+
+/// A Graphviz graph that can be written out in the DOT language, so that
+/// puzzle inputs can be looked at with `dot -Tsvg`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dot
+{
+	name: String,
+	is_directed: bool,
+	nodes: Vec<Element>,
+	edges: Vec<Element>,
+}
+
+/// A node or an edge, with its attributes in the order they were set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Element
+{
+	id: String,
+	/// The node an edge leads to; `None` for nodes.
+	to: Option<String>,
+	attributes: Vec<(&'static str, String)>,
+}
+
+impl Dot
+{
+	pub fn digraph(name: impl Into<String>) -> Dot
+	{
+		Dot {
+			name: name.into(),
+			is_directed: true,
+			nodes: Vec::new(),
+			edges: Vec::new(),
+		}
+	}
+
+	pub fn graph(name: impl Into<String>) -> Dot
+	{
+		Dot {
+			is_directed: false,
+			..Dot::digraph(name)
+		}
+	}
+
+	pub fn node(&mut self, id: impl ToString) -> &mut Element
+	{
+		self.nodes.push(Element {
+			id: id.to_string(),
+			to: None,
+			attributes: Vec::new(),
+		});
+		self.nodes.last_mut().unwrap()
+	}
+
+	pub fn edge(
+		&mut self,
+		from: impl ToString,
+		to: impl ToString,
+	) -> &mut Element
+	{
+		self.edges.push(Element {
+			id: from.to_string(),
+			to: Some(to.to_string()),
+			attributes: Vec::new(),
+		});
+		self.edges.last_mut().unwrap()
+	}
+
+	pub fn num_nodes(&self) -> usize
+	{
+		self.nodes.len()
+	}
+
+	pub fn num_edges(&self) -> usize
+	{
+		self.edges.len()
+	}
+
+	pub fn write(&self, path: &std::path::Path) -> std::io::Result<()>
+	{
+		std::fs::write(path, self.to_string())
+	}
+}
+
+impl Element
+{
+	pub fn attribute(
+		&mut self,
+		key: &'static str,
+		value: impl ToString,
+	) -> &mut Element
+	{
+		self.attributes.push((key, value.to_string()));
+		self
+	}
+
+	pub fn label(&mut self, label: impl ToString) -> &mut Element
+	{
+		self.attribute("label", label)
+	}
+
+	pub fn color(&mut self, color: &str) -> &mut Element
+	{
+		self.attribute("color", color)
+	}
+
+	pub fn shape(&mut self, shape: &str) -> &mut Element
+	{
+		self.attribute("shape", shape)
+	}
+}
+
+/// Every id and value is quoted, so that names like `in` or `1a` (which are
+/// keywords or invalid as bare ids) come out right.
+fn quote(s: &str) -> String
+{
+	let mut quoted = String::with_capacity(s.len() + 2);
+	quoted.push('"');
+	for x in s.chars()
+	{
+		match x
+		{
+			'"' => quoted.push_str("\\\""),
+			'\\' => quoted.push_str("\\\\"),
+			'\n' => quoted.push_str("\\n"),
+			_ => quoted.push(x),
+		}
+	}
+	quoted.push('"');
+	quoted
+}
+
+impl std::fmt::Display for Dot
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let (keyword, arrow) = if self.is_directed
+		{
+			("digraph", "->")
+		}
+		else
+		{
+			("graph", "--")
+		};
+		writeln!(f, "{keyword} {} {{", quote(&self.name))?;
+		for element in self.nodes.iter().chain(&self.edges)
+		{
+			let mut line = format!("\t{}", quote(&element.id));
+			if let Some(to) = &element.to
+			{
+				write!(line, " {arrow} {}", quote(to))?;
+			}
+			if !element.attributes.is_empty()
+			{
+				let attributes: Vec<String> = element
+					.attributes
+					.iter()
+					.map(|(key, value)| format!("{key}={}", quote(value)))
+					.collect();
+				write!(line, " [{}]", attributes.join(", "))?;
+			}
+			writeln!(f, "{line};")?;
+		}
+		writeln!(f, "}}")
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_digraph()
+	{
+		let mut dot = Dot::digraph("day20");
+		dot.node("broadcaster").shape("box");
+		dot.node("a").label("%a").color("blue");
+		dot.node("in");
+		dot.edge("broadcaster", "a");
+		dot.edge("a", "in").label(2);
+		assert_eq!((dot.num_nodes(), dot.num_edges()), (3, 2));
+		assert_eq!(
+			dot.to_string(),
+			"digraph \"day20\" {\n\t\"broadcaster\" [shape=\"box\"];\n\t\"a\" \
+			 [label=\"%a\", color=\"blue\"];\n\t\"in\";\n\t\"broadcaster\" -> \
+			 \"a\";\n\t\"a\" -> \"in\" [label=\"2\"];\n}\n"
+		);
+	}
+
+	#[test]
+	fn test_graph()
+	{
+		let mut dot = Dot::graph("trails");
+		dot.edge(0, 1).label("say \"hi\"\\\nbye");
+		assert_eq!(
+			dot.to_string(),
+			"graph \"trails\" {\n\t\"0\" -- \"1\" [label=\"say \
+			 \\\"hi\\\"\\\\\\nbye\"];\n}\n"
+		);
+	}
+}
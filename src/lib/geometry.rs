@@ -0,0 +1,481 @@
+/**/
+
+use std::ops::{Add, Sub};
+
+/// The integer types a [`Point`] can be made of.
+pub trait Coordinate:
+	Copy
+	+ Default
+	+ Ord
+	+ std::hash::Hash
+	+ std::fmt::Debug
+	+ Add<Output = Self>
+	+ Sub<Output = Self>
+{
+	const ZERO: Self;
+	const ONE: Self;
+
+	fn checked_add(self, other: Self) -> Option<Self>;
+	fn checked_sub(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_coordinate {
+	($($t:ty)*) => {
+		$(
+			impl Coordinate for $t
+			{
+				const ZERO: Self = 0;
+				const ONE: Self = 1;
+
+				fn checked_add(self, other: Self) -> Option<Self>
+				{
+					<$t>::checked_add(self, other)
+				}
+
+				fn checked_sub(self, other: Self) -> Option<Self>
+				{
+					<$t>::checked_sub(self, other)
+				}
+			}
+		)*
+	};
+}
+
+impl_coordinate!(u8 u16 u32 u64 usize i8 i16 i32 i64 isize);
+
+/// A position on a grid; rows grow downwards and columns to the right.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point<T = i32>
+{
+	pub row: T,
+	pub col: T,
+}
+
+impl<T: Coordinate> Point<T>
+{
+	pub fn new(row: T, col: T) -> Point<T>
+	{
+		Point { row, col }
+	}
+
+	pub fn manhattan_distance(self, other: Point<T>) -> T
+	{
+		let dr = self.row.max(other.row) - self.row.min(other.row);
+		let dc = self.col.max(other.col) - self.col.min(other.col);
+		dr + dc
+	}
+
+	/// The neighbouring point, or `None` if it cannot be represented.
+	pub fn checked_step(self, direction: Direction) -> Option<Point<T>>
+	{
+		self.checked_step_by(direction, T::ONE)
+	}
+
+	pub fn checked_step_by(
+		self,
+		direction: Direction,
+		distance: T,
+	) -> Option<Point<T>>
+	{
+		let Point { row, col } = self;
+		match direction
+		{
+			Direction::Up => Some(Point::new(row.checked_sub(distance)?, col)),
+			Direction::Right =>
+			{
+				Some(Point::new(row, col.checked_add(distance)?))
+			}
+			Direction::Down =>
+			{
+				Some(Point::new(row.checked_add(distance)?, col))
+			}
+			Direction::Left =>
+			{
+				Some(Point::new(row, col.checked_sub(distance)?))
+			}
+		}
+	}
+
+	/// The neighbouring point; panics (in debug builds) on overflow.
+	pub fn step(self, direction: Direction) -> Point<T>
+	{
+		self.step_by(direction, T::ONE)
+	}
+
+	pub fn step_by(self, direction: Direction, distance: T) -> Point<T>
+	{
+		let Point { row, col } = self;
+		match direction
+		{
+			Direction::Up => Point::new(row - distance, col),
+			Direction::Right => Point::new(row, col + distance),
+			Direction::Down => Point::new(row + distance, col),
+			Direction::Left => Point::new(row, col - distance),
+		}
+	}
+
+	/// The neighbouring point if it lies within `num_rows` by `num_cols`
+	/// starting at the origin.
+	pub fn step_within(
+		self,
+		direction: Direction,
+		num_rows: T,
+		num_cols: T,
+	) -> Option<Point<T>>
+	{
+		self.checked_step(direction)
+			.filter(|x| x.is_within(num_rows, num_cols))
+	}
+
+	pub fn is_within(self, num_rows: T, num_cols: T) -> bool
+	{
+		(T::ZERO..num_rows).contains(&self.row)
+			&& (T::ZERO..num_cols).contains(&self.col)
+	}
+
+	/// The neighbouring point in one of eight directions, or `None` if it
+	/// cannot be represented.
+	pub fn checked_step8(self, direction: Direction8) -> Option<Point<T>>
+	{
+		direction
+			.components()
+			.into_iter()
+			.flatten()
+			.try_fold(self, |point, x| point.checked_step(x))
+	}
+}
+
+/// One of the four orthogonal directions, in clockwise order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction
+{
+	#[default]
+	Up,
+	Right,
+	Down,
+	Left,
+}
+
+impl Direction
+{
+	pub const ALL: [Direction; 4] = [
+		Direction::Up,
+		Direction::Right,
+		Direction::Down,
+		Direction::Left,
+	];
+
+	pub fn turn_left(self) -> Direction
+	{
+		match self
+		{
+			Direction::Up => Direction::Left,
+			Direction::Right => Direction::Up,
+			Direction::Down => Direction::Right,
+			Direction::Left => Direction::Down,
+		}
+	}
+
+	pub fn turn_right(self) -> Direction
+	{
+		match self
+		{
+			Direction::Up => Direction::Right,
+			Direction::Right => Direction::Down,
+			Direction::Down => Direction::Left,
+			Direction::Left => Direction::Up,
+		}
+	}
+
+	pub fn reverse(self) -> Direction
+	{
+		match self
+		{
+			Direction::Up => Direction::Down,
+			Direction::Right => Direction::Left,
+			Direction::Down => Direction::Up,
+			Direction::Left => Direction::Right,
+		}
+	}
+
+	pub fn is_horizontal(self) -> bool
+	{
+		matches!(self, Direction::Left | Direction::Right)
+	}
+
+	pub fn is_vertical(self) -> bool
+	{
+		!self.is_horizontal()
+	}
+
+	/// `^`, `>`, `v` or `<`.
+	pub fn arrow(self) -> char
+	{
+		match self
+		{
+			Direction::Up => '^',
+			Direction::Right => '>',
+			Direction::Down => 'v',
+			Direction::Left => '<',
+		}
+	}
+
+	pub fn from_arrow(arrow: char) -> Option<Direction>
+	{
+		match arrow
+		{
+			'^' => Some(Direction::Up),
+			'>' => Some(Direction::Right),
+			'v' => Some(Direction::Down),
+			'<' => Some(Direction::Left),
+			_ => None,
+		}
+	}
+}
+
+/// Formats as `U`, `R`, `D` or `L`.
+impl std::fmt::Display for Direction
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let letter = match self
+		{
+			Direction::Up => "U",
+			Direction::Right => "R",
+			Direction::Down => "D",
+			Direction::Left => "L",
+		};
+		f.write_str(letter)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDirectionError(pub String);
+
+impl std::fmt::Display for ParseDirectionError
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		write!(f, "invalid direction '{}'", self.0)
+	}
+}
+
+impl std::error::Error for ParseDirectionError {}
+
+/// Parses `U`, `R`, `D` and `L` as well as the arrows `^`, `>`, `v` and `<`.
+impl std::str::FromStr for Direction
+{
+	type Err = ParseDirectionError;
+
+	fn from_str(s: &str) -> Result<Direction, ParseDirectionError>
+	{
+		match s
+		{
+			"U" | "^" => Ok(Direction::Up),
+			"R" | ">" => Ok(Direction::Right),
+			"D" | "v" => Ok(Direction::Down),
+			"L" | "<" => Ok(Direction::Left),
+			_ => Err(ParseDirectionError(s.to_string())),
+		}
+	}
+}
+
+/// One of the four orthogonal and four diagonal directions, in clockwise
+/// order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction8
+{
+	#[default]
+	Up,
+	UpRight,
+	Right,
+	DownRight,
+	Down,
+	DownLeft,
+	Left,
+	UpLeft,
+}
+
+impl Direction8
+{
+	pub const ALL: [Direction8; 8] = [
+		Direction8::Up,
+		Direction8::UpRight,
+		Direction8::Right,
+		Direction8::DownRight,
+		Direction8::Down,
+		Direction8::DownLeft,
+		Direction8::Left,
+		Direction8::UpLeft,
+	];
+
+	fn from_index(i: usize) -> Direction8
+	{
+		Direction8::ALL[i % 8]
+	}
+
+	/// Turns 45 degrees counterclockwise.
+	pub fn turn_left(self) -> Direction8
+	{
+		Direction8::from_index(self as usize + 7)
+	}
+
+	/// Turns 45 degrees clockwise.
+	pub fn turn_right(self) -> Direction8
+	{
+		Direction8::from_index(self as usize + 1)
+	}
+
+	pub fn reverse(self) -> Direction8
+	{
+		Direction8::from_index(self as usize + 4)
+	}
+
+	pub fn is_diagonal(self) -> bool
+	{
+		self as usize % 2 == 1
+	}
+
+	/// The vertical and horizontal steps this direction is made of.
+	pub fn components(self) -> [Option<Direction>; 2]
+	{
+		let vertical = match self
+		{
+			Direction8::UpLeft | Direction8::Up | Direction8::UpRight =>
+			{
+				Some(Direction::Up)
+			}
+			Direction8::DownLeft | Direction8::Down | Direction8::DownRight =>
+			{
+				Some(Direction::Down)
+			}
+			Direction8::Left | Direction8::Right => None,
+		};
+		let horizontal =
+			match self
+			{
+				Direction8::UpLeft
+				| Direction8::Left
+				| Direction8::DownLeft => Some(Direction::Left),
+				Direction8::UpRight
+				| Direction8::Right
+				| Direction8::DownRight => Some(Direction::Right),
+				Direction8::Up | Direction8::Down => None,
+			};
+		[vertical, horizontal]
+	}
+}
+
+impl From<Direction> for Direction8
+{
+	fn from(direction: Direction) -> Direction8
+	{
+		match direction
+		{
+			Direction::Up => Direction8::Up,
+			Direction::Right => Direction8::Right,
+			Direction::Down => Direction8::Down,
+			Direction::Left => Direction8::Left,
+		}
+	}
+}
+
+/// Formats as `N`, `NE`, `E`, `SE`, `S`, `SW`, `W` or `NW`.
+impl std::fmt::Display for Direction8
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		const NAMES: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+		f.write_str(NAMES[*self as usize])
+	}
+}
+
+impl std::str::FromStr for Direction8
+{
+	type Err = ParseDirectionError;
+
+	fn from_str(s: &str) -> Result<Direction8, ParseDirectionError>
+	{
+		match s
+		{
+			"N" => Ok(Direction8::Up),
+			"NE" => Ok(Direction8::UpRight),
+			"E" => Ok(Direction8::Right),
+			"SE" => Ok(Direction8::DownRight),
+			"S" => Ok(Direction8::Down),
+			"SW" => Ok(Direction8::DownLeft),
+			"W" => Ok(Direction8::Left),
+			"NW" => Ok(Direction8::UpLeft),
+			_ => Err(ParseDirectionError(s.to_string())),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_direction()
+	{
+		for direction in Direction::ALL
+		{
+			assert_eq!(direction.turn_left().turn_right(), direction);
+			assert_eq!(
+				direction.turn_right().turn_right(),
+				direction.reverse()
+			);
+			assert_eq!(direction.to_string().parse(), Ok(direction));
+			assert_eq!(
+				Direction::from_arrow(direction.arrow()),
+				Some(direction)
+			);
+		}
+		assert_eq!("v".parse(), Ok(Direction::Down));
+		assert!("X".parse::<Direction>().is_err());
+		assert!(Direction::Left.is_horizontal());
+		assert!(Direction::Up.is_vertical());
+	}
+
+	#[test]
+	fn test_direction8()
+	{
+		for direction in Direction8::ALL
+		{
+			assert_eq!(direction.turn_left().turn_right(), direction);
+			assert_eq!(direction.reverse().reverse(), direction);
+			assert_eq!(direction.to_string().parse(), Ok(direction));
+		}
+		assert_eq!(Direction8::Up.turn_left(), Direction8::UpLeft);
+		assert_eq!(Direction8::from(Direction::Down), Direction8::Down);
+		assert!(Direction8::DownLeft.is_diagonal());
+	}
+
+	#[test]
+	fn test_step()
+	{
+		let origin: Point<u8> = Point::new(0, 0);
+		assert_eq!(origin.checked_step(Direction::Up), None);
+		assert_eq!(origin.step(Direction::Right), Point::new(0, 1));
+		assert_eq!(
+			origin.step_within(Direction::Down, 2, 2),
+			Some(Point::new(1, 0))
+		);
+		assert_eq!(origin.step_within(Direction::Down, 1, 2), None);
+		assert_eq!(
+			origin.checked_step8(Direction8::DownRight),
+			Some(Point::new(1, 1))
+		);
+		assert_eq!(origin.checked_step8(Direction8::UpRight), None);
+
+		let a = Point::new(-3, 4);
+		assert_eq!(a.step_by(Direction::Left, 10), Point::new(-3, -6));
+		assert_eq!(a.manhattan_distance(Point::new(2, -1)), 10);
+		assert_eq!(
+			Point::<u16>::new(5, 1).manhattan_distance(Point::new(1, 5)),
+			8
+		);
+	}
+}
@@ -0,0 +1,411 @@
+/**/
+
+use crate::parse::ParseError;
+
+/// A rectangular grid stored row by row. Indexing with a row number gives
+/// that row as a slice, so `grid[row][col]` works like it does for nested
+/// arrays; [`Grid::get`] is the non-panicking alternative.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Grid<T>
+{
+	width: usize,
+	height: usize,
+	cells: Vec<T>,
+}
+
+impl<T> Grid<T>
+{
+	pub fn new(width: usize, height: usize, fill: T) -> Grid<T>
+	where
+		T: Clone,
+	{
+		Grid {
+			width,
+			height,
+			cells: vec![fill; width * height],
+		}
+	}
+
+	pub fn from_fn(
+		width: usize,
+		height: usize,
+		mut f: impl FnMut(usize, usize) -> T,
+	) -> Grid<T>
+	{
+		let cells = (0..height)
+			.flat_map(|row| (0..width).map(move |col| (row, col)))
+			.map(|(row, col)| f(row, col))
+			.collect();
+		Grid {
+			width,
+			height,
+			cells,
+		}
+	}
+
+	/// Builds a grid from the non-empty lines of `input`, mapping each
+	/// character to a cell. Panics if the lines are not all the same length.
+	pub fn parse(input: &str, mut f: impl FnMut(char) -> T) -> Grid<T>
+	{
+		let mut width = None;
+		let mut height = 0;
+		let mut cells = Vec::with_capacity(input.len());
+		for line in input.lines().filter(|x| !x.is_empty())
+		{
+			let len_before = cells.len();
+			cells.extend(line.chars().map(&mut f));
+			let line_width = cells.len() - len_before;
+			match width
+			{
+				None => width = Some(line_width),
+				Some(width) => assert_eq!(
+					line_width,
+					width,
+					"line {} has a different width than the first",
+					height + 1
+				),
+			}
+			height += 1;
+		}
+		Grid {
+			width: width.unwrap_or(0),
+			height,
+			cells,
+		}
+	}
+
+	/// Like [`Grid::parse`], but reports characters that `f` rejects and
+	/// ragged lines as errors; `expected` describes the accepted characters.
+	pub fn try_parse(
+		input: &str,
+		expected: &str,
+		mut f: impl FnMut(char) -> Option<T>,
+	) -> Result<Grid<T>, ParseError>
+	{
+		let mut width = None;
+		let mut height = 0;
+		let mut cells = Vec::with_capacity(input.len());
+		for line in input.lines().filter(|x| !x.is_empty())
+		{
+			let len_before = cells.len();
+			for (i, x) in line.char_indices()
+			{
+				let cell = f(x).ok_or_else(|| {
+					ParseError::at(
+						input,
+						&line[i..],
+						format!("expected {expected}, found '{x}'"),
+					)
+				})?;
+				cells.push(cell);
+			}
+			let line_width = cells.len() - len_before;
+			match width
+			{
+				None => width = Some(line_width),
+				Some(width) if width != line_width =>
+				{
+					return Err(ParseError::at(
+						input,
+						line,
+						format!("expected {width} columns like the first line"),
+					));
+				}
+				Some(_) => (),
+			}
+			height += 1;
+		}
+		Ok(Grid {
+			width: width.unwrap_or(0),
+			height,
+			cells,
+		})
+	}
+
+	pub fn width(&self) -> usize
+	{
+		self.width
+	}
+
+	pub fn height(&self) -> usize
+	{
+		self.height
+	}
+
+	pub fn contains(&self, row: usize, col: usize) -> bool
+	{
+		row < self.height && col < self.width
+	}
+
+	pub fn get(&self, row: usize, col: usize) -> Option<&T>
+	{
+		if self.contains(row, col)
+		{
+			Some(&self.cells[row * self.width + col])
+		}
+		else
+		{
+			None
+		}
+	}
+
+	pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T>
+	{
+		if self.contains(row, col)
+		{
+			Some(&mut self.cells[row * self.width + col])
+		}
+		else
+		{
+			None
+		}
+	}
+
+	pub fn rows(&self) -> std::slice::ChunksExact<'_, T>
+	{
+		self.cells.chunks_exact(self.width.max(1))
+	}
+
+	pub fn column(&self, col: usize) -> impl Iterator<Item = &T> + '_
+	{
+		assert!(col < self.width, "column {col} out of bounds");
+		self.cells.iter().skip(col).step_by(self.width)
+	}
+
+	pub fn columns(
+		&self,
+	) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_
+	{
+		(0..self.width).map(|col| self.column(col))
+	}
+
+	/// Every `(row, col)` in row-major order.
+	pub fn positions(&self) -> impl Iterator<Item = (usize, usize)>
+	{
+		let width = self.width;
+		(0..self.height)
+			.flat_map(move |row| (0..width).map(move |col| (row, col)))
+	}
+
+	pub fn position(
+		&self,
+		predicate: impl FnMut(&T) -> bool,
+	) -> Option<(usize, usize)>
+	{
+		let i = self.cells.iter().position(predicate)?;
+		Some((i / self.width, i % self.width))
+	}
+
+	/// The up to four orthogonal neighbours that lie inside the grid.
+	pub fn neighbours(
+		&self,
+		row: usize,
+		col: usize,
+	) -> impl Iterator<Item = (usize, usize)> + '_
+	{
+		self.offsets(row, col, &[(-1, 0), (0, 1), (1, 0), (0, -1)])
+	}
+
+	/// The up to eight orthogonal and diagonal neighbours that lie inside the
+	/// grid.
+	pub fn neighbours_with_diagonals(
+		&self,
+		row: usize,
+		col: usize,
+	) -> impl Iterator<Item = (usize, usize)> + '_
+	{
+		self.offsets(
+			row,
+			col,
+			&[
+				(-1, -1),
+				(-1, 0),
+				(-1, 1),
+				(0, 1),
+				(1, 1),
+				(1, 0),
+				(1, -1),
+				(0, -1),
+			],
+		)
+	}
+
+	fn offsets<'a>(
+		&'a self,
+		row: usize,
+		col: usize,
+		offsets: &'a [(isize, isize)],
+	) -> impl Iterator<Item = (usize, usize)> + 'a
+	{
+		offsets.iter().filter_map(move |&(dr, dc)| {
+			let r = row.checked_add_signed(dr)?;
+			let c = col.checked_add_signed(dc)?;
+			self.contains(r, c).then_some((r, c))
+		})
+	}
+
+	pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U>
+	{
+		Grid {
+			width: self.width,
+			height: self.height,
+			cells: self.cells.iter().map(f).collect(),
+		}
+	}
+
+	/// A copy with `margin` extra cells of `fill` on every side.
+	pub fn with_border(&self, margin: usize, fill: T) -> Grid<T>
+	where
+		T: Clone,
+	{
+		Grid::from_fn(
+			self.width + 2 * margin,
+			self.height + 2 * margin,
+			|row, col| {
+				row.checked_sub(margin)
+					.zip(col.checked_sub(margin))
+					.and_then(|(r, c)| self.get(r, c))
+					.unwrap_or(&fill)
+					.clone()
+			},
+		)
+	}
+
+	pub fn iter(&self) -> std::slice::Iter<'_, T>
+	{
+		self.cells.iter()
+	}
+
+	pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T>
+	{
+		self.cells.iter_mut()
+	}
+
+	pub fn fill(&mut self, value: T)
+	where
+		T: Clone,
+	{
+		self.cells.fill(value);
+	}
+}
+
+impl<T> std::ops::Index<usize> for Grid<T>
+{
+	type Output = [T];
+
+	fn index(&self, row: usize) -> &[T]
+	{
+		assert!(row < self.height, "row {row} out of bounds");
+		&self.cells[row * self.width..(row + 1) * self.width]
+	}
+}
+
+impl<T> std::ops::IndexMut<usize> for Grid<T>
+{
+	fn index_mut(&mut self, row: usize) -> &mut [T]
+	{
+		assert!(row < self.height, "row {row} out of bounds");
+		&mut self.cells[row * self.width..(row + 1) * self.width]
+	}
+}
+
+impl std::fmt::Display for Grid<char>
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		for row in self.rows()
+		{
+			writeln!(f, "{}", row.iter().collect::<String>())?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_parse()
+	{
+		let grid = Grid::parse("#.#\n..#\n\n", |x| x == '#');
+		assert_eq!(grid.width(), 3);
+		assert_eq!(grid.height(), 2);
+		assert_eq!(&grid[0], &[true, false, true]);
+		assert_eq!(grid.get(1, 2), Some(&true));
+		assert_eq!(grid.get(2, 0), None);
+		assert_eq!(grid.get(0, 3), None);
+		assert_eq!(
+			grid.column(2).copied().collect::<Vec<bool>>(),
+			vec![true, true]
+		);
+		assert_eq!(grid.columns().count(), 3);
+		assert_eq!(grid.rows().count(), 2);
+		assert_eq!(grid.position(|&x| !x), Some((0, 1)));
+		assert_eq!(grid.positions().last(), Some((1, 2)));
+	}
+
+	#[test]
+	fn test_try_parse()
+	{
+		let input = "#.#\n..#\n\n#x.\n";
+		let cell = |x| match x
+		{
+			'#' => Some(true),
+			'.' => Some(false),
+			_ => None,
+		};
+		assert_eq!(
+			Grid::try_parse(&input[..8], "'#' or '.'", cell),
+			Ok(Grid::parse(&input[..8], |x| x == '#'))
+		);
+		assert_eq!(
+			Grid::try_parse(input, "'#' or '.'", cell)
+				.unwrap_err()
+				.to_string(),
+			"input line 4 col 2: expected '#' or '.', found 'x'"
+		);
+		assert_eq!(
+			Grid::try_parse("##\n#", "'#' or '.'", cell)
+				.unwrap_err()
+				.to_string(),
+			"input line 2 col 1: expected 2 columns like the first line"
+		);
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_parse_ragged()
+	{
+		Grid::parse("###\n##\n", |x| x);
+	}
+
+	#[test]
+	fn test_neighbours()
+	{
+		let grid = Grid::new(3, 2, 0u8);
+		assert_eq!(
+			grid.neighbours(0, 0).collect::<Vec<_>>(),
+			vec![(0, 1), (1, 0)]
+		);
+		assert_eq!(
+			grid.neighbours(1, 1).collect::<Vec<_>>(),
+			vec![(0, 1), (1, 2), (1, 0)]
+		);
+		assert_eq!(grid.neighbours_with_diagonals(0, 1).count(), 5);
+	}
+
+	#[test]
+	fn test_with_border()
+	{
+		let grid = Grid::parse("ab\ncd", |x| x);
+		let padded = grid.with_border(1, '.');
+		assert_eq!(padded.to_string(), "....\n.ab.\n.cd.\n....\n");
+		assert_eq!(
+			grid.map(|x| x.to_ascii_uppercase()).to_string(),
+			"AB\nCD\n"
+		);
+	}
+}
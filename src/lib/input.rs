@@ -0,0 +1,183 @@
+/**/
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource
+{
+	File(PathBuf),
+	Stdin,
+}
+
+impl InputSource
+{
+	/// Interprets a command line argument: `-` reads from stdin, any other
+	/// value is a path, and no argument at all means the day's default.
+	pub fn from_arg(arg: Option<&str>, day: &str) -> InputSource
+	{
+		match arg
+		{
+			Some("-") => InputSource::Stdin,
+			Some(path) => InputSource::File(PathBuf::from(path)),
+			None => InputSource::File(default_path(day)),
+		}
+	}
+
+	pub fn read(&self) -> Result<String, InputError>
+	{
+		let result = match self
+		{
+			InputSource::File(path) => std::fs::read_to_string(path),
+			InputSource::Stdin =>
+			{
+				let mut buffer = String::new();
+				std::io::stdin().read_to_string(&mut buffer).map(|_| buffer)
+			}
+		};
+		result.map_err(|error| InputError {
+			source: self.clone(),
+			error,
+		})
+	}
+}
+
+impl std::fmt::Display for InputSource
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		match self
+		{
+			InputSource::File(path) => write!(f, "{}", path.display()),
+			InputSource::Stdin => write!(f, "stdin"),
+		}
+	}
+}
+
+#[derive(Debug)]
+pub struct InputError
+{
+	pub source: InputSource,
+	pub error: std::io::Error,
+}
+
+impl std::fmt::Display for InputError
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		if self.error.kind() == std::io::ErrorKind::NotFound
+		{
+			write!(
+				f,
+				"no puzzle input found at {}; provide another path, or '-' to \
+				 read from stdin",
+				self.source
+			)
+		}
+		else
+		{
+			write!(
+				f,
+				"failed to read input from {}: {}",
+				self.source, self.error
+			)
+		}
+	}
+}
+
+impl std::error::Error for InputError
+{
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)>
+	{
+		Some(&self.error)
+	}
+}
+
+/// The `input.txt` next to the day's `main.rs`, regardless of the working
+/// directory.
+pub fn default_path(day: &str) -> PathBuf
+{
+	Path::new(env!("CARGO_MANIFEST_DIR"))
+		.join("src")
+		.join("bin")
+		.join(day)
+		.join("input.txt")
+}
+
+pub fn load(day: &str) -> Result<String, InputError>
+{
+	let arg = std::env::args().nth(1);
+	InputSource::from_arg(arg.as_deref(), day).read()
+}
+
+pub fn load_or_exit(day: &str) -> String
+{
+	match load(day)
+	{
+		Ok(input) => input,
+		Err(error) =>
+		{
+			eprintln!("{day}: {error}");
+			std::process::exit(1);
+		}
+	}
+}
+
+/// Turns `src/bin/day01/main.rs` into `day01`.
+pub fn day_from_source_path(path: &str) -> &str
+{
+	Path::new(path)
+		.parent()
+		.and_then(|dir| dir.file_name())
+		.and_then(|name| name.to_str())
+		.unwrap_or(path)
+}
+
+#[macro_export]
+macro_rules! load_input {
+	() => {
+		$crate::input::load_or_exit($crate::input::day_from_source_path(
+			std::file!(),
+		))
+	};
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_day_from_source_path()
+	{
+		assert_eq!(day_from_source_path("src/bin/day01/main.rs"), "day01");
+		assert_eq!(day_from_source_path("src/template/main.rs"), "template");
+	}
+
+	#[test]
+	fn test_from_arg()
+	{
+		assert_eq!(
+			InputSource::from_arg(Some("-"), "day01"),
+			InputSource::Stdin
+		);
+		assert_eq!(
+			InputSource::from_arg(Some("other.txt"), "day01"),
+			InputSource::File(PathBuf::from("other.txt"))
+		);
+		assert_eq!(
+			InputSource::from_arg(None, "day01"),
+			InputSource::File(default_path("day01"))
+		);
+		assert!(default_path("day01").ends_with("src/bin/day01/input.txt"));
+	}
+
+	#[test]
+	fn test_missing_input()
+	{
+		let source = InputSource::File(PathBuf::from("does/not/exist.txt"));
+		let error = source.read().unwrap_err();
+		assert!(error.to_string().contains("does/not/exist.txt"));
+	}
+}
@@ -0,0 +1,550 @@
+/**/
+
+use crate::dot::Dot;
+use crate::geometry::{Direction, Point};
+use crate::grid::Grid;
+use bitvec::vec::BitVec;
+use std::cmp::Reverse;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A maze on a grid, compressed to the cells where corridors meet. Every
+/// corridor between two junctions becomes an edge as long as the corridor.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JunctionGraph
+{
+	pub junctions: Vec<Point<usize>>,
+	/// The edges leaving each junction.
+	pub edges: Vec<Vec<Edge>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge
+{
+	pub to: usize,
+	pub length: usize,
+}
+
+/// A longest simple path: its length and the junctions it visits, from the
+/// first to the last.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LongestPath
+{
+	pub length: usize,
+	pub junctions: Vec<usize>,
+}
+
+impl JunctionGraph
+{
+	/// Junctions are the open cells with three or more open neighbours,
+	/// plus the `terminals`, numbered in row-major order. `can_step` says
+	/// whether one may move out of an open cell in a direction; if it is
+	/// the same both ways along every corridor the graph is undirected in
+	/// effect, otherwise (with slopes, say) corridors that cannot be walked
+	/// end to end only give an edge one way. Dead ends are left out.
+	pub fn from_grid<T>(
+		grid: &Grid<T>,
+		terminals: &[Point<usize>],
+		is_open: impl Fn(&T) -> bool,
+		can_step: impl Fn(&T, Direction) -> bool,
+	) -> JunctionGraph
+	{
+		let mut index = grid.map(|_| None);
+		let mut junctions = Vec::new();
+		for (row, col) in grid.positions()
+		{
+			let at = Point::new(row, col);
+			if is_open(&grid[row][col])
+				&& (terminals.contains(&at)
+					|| open_neighbours(grid, &is_open, at).count() >= 3)
+			{
+				index[row][col] = Some(junctions.len());
+				junctions.push(at);
+			}
+		}
+
+		let mut edges = vec![Vec::new(); junctions.len()];
+		for (i, &start) in junctions.iter().enumerate()
+		{
+			'corridors: for (direction, _) in
+				open_neighbours(grid, &is_open, start)
+			{
+				let mut prev;
+				let mut at = start;
+				let mut facing = direction;
+				let mut length = 0;
+				loop
+				{
+					if !can_step(&grid[at.row][at.col], facing)
+					{
+						continue 'corridors;
+					}
+					prev = at;
+					at = at.step(facing);
+					length += 1;
+					if let Some(j) = index[at.row][at.col]
+					{
+						edges[i].push(Edge { to: j, length });
+						continue 'corridors;
+					}
+					match open_neighbours(grid, &is_open, at)
+						.find(|&(_, next)| next != prev)
+					{
+						Some((direction, _)) => facing = direction,
+						None => continue 'corridors,
+					}
+				}
+			}
+		}
+		JunctionGraph { junctions, edges }
+	}
+
+	pub fn len(&self) -> usize
+	{
+		self.junctions.len()
+	}
+
+	pub fn is_empty(&self) -> bool
+	{
+		self.junctions.is_empty()
+	}
+
+	/// The same graph with every edge also going the other way.
+	pub fn to_undirected(&self) -> JunctionGraph
+	{
+		let mut edges = self.edges.clone();
+		for (i, outgoing) in self.edges.iter().enumerate()
+		{
+			for edge in outgoing
+			{
+				let reverse = Edge {
+					to: i,
+					length: edge.length,
+				};
+				if !edges[edge.to].contains(&reverse)
+				{
+					edges[edge.to].push(reverse);
+				}
+			}
+		}
+		JunctionGraph {
+			junctions: self.junctions.clone(),
+			edges,
+		}
+	}
+
+	/// The longest path from junction `from` to junction `to` that visits
+	/// no junction twice, or `None` if `to` cannot be reached.
+	pub fn longest_path(&self, from: usize, to: usize) -> Option<LongestPath>
+	{
+		let mut search = LongestPathSearch::new(self, to, None);
+		search.explore_from(&[from], 0);
+		search.best
+	}
+
+	/// Like [`JunctionGraph::longest_path`], but the paths `split_depth`
+	/// edges long are explored separately on `num_threads` threads, which
+	/// prune with the longest length any of them has found. The path found
+	/// is the same: of the longest paths, the one the single-threaded
+	/// search would reach first.
+	pub fn longest_path_parallel(
+		&self,
+		from: usize,
+		to: usize,
+		split_depth: usize,
+		num_threads: usize,
+	) -> Option<LongestPath>
+	{
+		let mut prefixes = Vec::new();
+		self.collect_prefixes(
+			&mut vec![from],
+			0,
+			to,
+			split_depth,
+			&mut prefixes,
+		);
+		let shared_best = AtomicUsize::new(0);
+		let next_prefix = AtomicUsize::new(0);
+		let found: Vec<(usize, LongestPath)> = std::thread::scope(|scope| {
+			let workers: Vec<_> = (0..num_threads.max(1))
+				.map(|_| {
+					scope.spawn(|| {
+						let mut search = LongestPathSearch::new(
+							self,
+							to,
+							Some(&shared_best),
+						);
+						let mut found = Vec::new();
+						loop
+						{
+							let k = next_prefix.fetch_add(1, Ordering::Relaxed);
+							let Some((route, length)) = prefixes.get(k)
+							else
+							{
+								break;
+							};
+							search.best = None;
+							search.explore_from(route, *length);
+							found.extend(search.best.take().map(|x| (k, x)));
+						}
+						found
+					})
+				})
+				.collect();
+			workers
+				.into_iter()
+				.flat_map(|worker| worker.join().unwrap())
+				.collect()
+		});
+		// Ties go to the earliest prefix, as they would without threads.
+		found
+			.into_iter()
+			.max_by_key(|(k, path)| (path.length, Reverse(*k)))
+			.map(|(_, path)| path)
+	}
+
+	/// The simple paths from the end of `route` that are `depth` edges
+	/// long or end at `to`, in depth-first order.
+	fn collect_prefixes(
+		&self,
+		route: &mut Vec<usize>,
+		length: usize,
+		to: usize,
+		depth: usize,
+		prefixes: &mut Vec<(Vec<usize>, usize)>,
+	)
+	{
+		let at = *route.last().unwrap();
+		if depth == 0 || at == to
+		{
+			prefixes.push((route.clone(), length));
+			return;
+		}
+		for edge in &self.edges[at]
+		{
+			if !route.contains(&edge.to)
+			{
+				route.push(edge.to);
+				self.collect_prefixes(
+					route,
+					length + edge.length,
+					to,
+					depth - 1,
+					prefixes,
+				);
+				route.pop();
+			}
+		}
+	}
+
+	/// Junctions labelled with their position and edges with their length.
+	pub fn to_dot(&self, name: &str) -> Dot
+	{
+		let mut dot = Dot::digraph(name);
+		for (i, at) in self.junctions.iter().enumerate()
+		{
+			dot.node(i).label(format!("r{}c{}", at.row, at.col));
+		}
+		for (i, outgoing) in self.edges.iter().enumerate()
+		{
+			for edge in outgoing
+			{
+				dot.edge(i, edge.to).label(edge.length);
+			}
+		}
+		dot
+	}
+}
+
+fn open_neighbours<'a, T>(
+	grid: &'a Grid<T>,
+	is_open: &'a impl Fn(&T) -> bool,
+	at: Point<usize>,
+) -> impl Iterator<Item = (Direction, Point<usize>)> + 'a
+{
+	Direction::ALL.into_iter().filter_map(move |direction| {
+		let next = at.step_within(direction, grid.height(), grid.width())?;
+		is_open(&grid[next.row][next.col]).then_some((direction, next))
+	})
+}
+
+/// A depth-first search over simple paths that gives up on a path once
+/// even taking the longest edge into every unvisited junction would not
+/// beat the best path so far, or once every junction with an edge into the
+/// destination has been visited. Paths only as long as the best one so far
+/// are still explored when the best was found by another thread, so that
+/// each thread finds the first of its longest paths.
+struct LongestPathSearch<'a>
+{
+	graph: &'a JunctionGraph,
+	to: usize,
+	max_incoming: Vec<usize>,
+	is_last_hop: BitVec,
+	num_last_hops_left: usize,
+	visited: BitVec,
+	route: Vec<usize>,
+	best: Option<LongestPath>,
+	shared_best: Option<&'a AtomicUsize>,
+}
+
+impl<'a> LongestPathSearch<'a>
+{
+	fn new(
+		graph: &'a JunctionGraph,
+		to: usize,
+		shared_best: Option<&'a AtomicUsize>,
+	) -> Self
+	{
+		let n = graph.len();
+		let mut max_incoming = vec![0; n];
+		let mut is_last_hop = BitVec::repeat(false, n);
+		for (i, outgoing) in graph.edges.iter().enumerate()
+		{
+			for edge in outgoing
+			{
+				let x = &mut max_incoming[edge.to];
+				*x = (*x).max(edge.length);
+				if edge.to == to && i != to
+				{
+					is_last_hop.set(i, true);
+				}
+			}
+		}
+		LongestPathSearch {
+			graph,
+			to,
+			max_incoming,
+			is_last_hop,
+			num_last_hops_left: 0,
+			visited: BitVec::repeat(false, n),
+			route: Vec::new(),
+			best: None,
+			shared_best,
+		}
+	}
+
+	/// Explores the paths that start with `route`, which is `length` long.
+	fn explore_from(&mut self, route: &[usize], length: usize)
+	{
+		self.visited.fill(false);
+		for &i in route
+		{
+			self.visited.set(i, true);
+		}
+		self.route.clear();
+		self.route.extend_from_slice(route);
+		self.num_last_hops_left =
+			route.iter().fold(self.is_last_hop.count_ones(), |x, &i| {
+				x - usize::from(self.is_last_hop[i])
+			});
+		let bound = self.max_incoming.iter().sum::<usize>()
+			- route.iter().map(|&i| self.max_incoming[i]).sum::<usize>();
+		self.explore(*route.last().unwrap(), length, bound);
+	}
+
+	fn explore(&mut self, at: usize, length: usize, bound: usize)
+	{
+		if at == self.to
+		{
+			if self.best.as_ref().is_none_or(|best| length > best.length)
+			{
+				self.best = Some(LongestPath {
+					length,
+					junctions: self.route.clone(),
+				});
+				if let Some(shared_best) = self.shared_best
+				{
+					shared_best.fetch_max(length, Ordering::Relaxed);
+				}
+			}
+			return;
+		}
+		if self.num_last_hops_left == 0 && !self.is_last_hop[at]
+		{
+			return;
+		}
+		if self
+			.best
+			.as_ref()
+			.is_some_and(|best| length + bound <= best.length)
+			|| self.shared_best.is_some_and(|shared_best| {
+				length + bound < shared_best.load(Ordering::Relaxed)
+			})
+		{
+			return;
+		}
+
+		let graph = self.graph;
+		for edge in &graph.edges[at]
+		{
+			let next = edge.to;
+			if self.visited[next]
+			{
+				continue;
+			}
+			self.visited.set(next, true);
+			self.route.push(next);
+			let is_last_hop = self.is_last_hop[next];
+			self.num_last_hops_left -= usize::from(is_last_hop);
+			self.explore(
+				next,
+				length + edge.length,
+				bound - self.max_incoming[next],
+			);
+			self.num_last_hops_left += usize::from(is_last_hop);
+			self.route.pop();
+			self.visited.set(next, false);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	// A loop between the second junction and the exit corridor, with a slope
+	// that lets its lower half be walked to the right only.
+	const MAZE: &str = "\
+#.#######
+#.......#
+#.#.###.#
+#.#...#.#
+#.###.#.#
+#...>...#
+#.#######
+";
+
+	fn parse(input: &str) -> JunctionGraph
+	{
+		let grid = Grid::parse(input, |x| x);
+		let terminals = [Point::new(0, 1), Point::new(6, 1)];
+		JunctionGraph::from_grid(
+			&grid,
+			&terminals,
+			|&x| x != '#',
+			|&x, direction| {
+				x == '.' || Direction::from_arrow(x) == Some(direction)
+			},
+		)
+	}
+
+	#[test]
+	fn test_from_grid()
+	{
+		let graph = parse(MAZE);
+		assert_eq!(
+			graph.junctions,
+			vec![
+				Point::new(0, 1),
+				Point::new(1, 1),
+				Point::new(1, 3),
+				Point::new(5, 1),
+				Point::new(5, 5),
+				Point::new(6, 1),
+			]
+		);
+		let edges = |i: usize| -> Vec<(usize, usize)> {
+			graph.edges[i].iter().map(|x| (x.to, x.length)).collect()
+		};
+		assert_eq!(edges(0), vec![(1, 1)]);
+		assert_eq!(edges(1), vec![(0, 1), (2, 2), (3, 4)]);
+		assert_eq!(edges(2), vec![(4, 10), (4, 6), (1, 2)]);
+		assert_eq!(edges(3), vec![(1, 4), (4, 4), (5, 1)]);
+		assert_eq!(edges(4), vec![(2, 6), (2, 10)]);
+		assert_eq!(edges(5), vec![(3, 1)]);
+	}
+
+	#[test]
+	fn test_longest_path()
+	{
+		let graph = parse(MAZE);
+		assert_eq!(
+			graph.longest_path(0, 5),
+			Some(LongestPath {
+				length: 6,
+				junctions: vec![0, 1, 3, 5],
+			})
+		);
+		assert_eq!(
+			graph.longest_path(5, 0),
+			Some(LongestPath {
+				length: 1 + 4 + 10 + 2 + 1,
+				junctions: vec![5, 3, 4, 2, 1, 0],
+			})
+		);
+		let undirected = graph.to_undirected();
+		assert_eq!(undirected.edges[4].len(), 3);
+		assert_eq!(
+			undirected.longest_path(0, 5).map(|x| x.junctions),
+			Some(vec![0, 1, 2, 4, 3, 5])
+		);
+		let unreachable = JunctionGraph {
+			junctions: vec![Point::new(0, 0); 2],
+			edges: vec![Vec::new(); 2],
+		};
+		assert_eq!(unreachable.longest_path(0, 1), None);
+		assert_eq!(unreachable.longest_path_parallel(0, 1, 2, 2), None);
+	}
+
+	#[test]
+	fn test_longest_path_parallel()
+	{
+		let graph = parse(MAZE);
+		for graph in [graph.to_undirected(), graph]
+		{
+			for (from, to) in [(0, 5), (5, 0), (1, 4)]
+			{
+				let expected = graph.longest_path(from, to);
+				for split_depth in 0..5
+				{
+					for num_threads in [1, 3]
+					{
+						assert_eq!(
+							graph.longest_path_parallel(
+								from,
+								to,
+								split_depth,
+								num_threads
+							),
+							expected
+						);
+					}
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_many_junctions()
+	{
+		// A corridor with a dead end below every other cell, each of which
+		// makes a junction.
+		let width = 141;
+		let spurs: String = (0..width)
+			.map(|x| {
+				if x % 2 == 0
+				{
+					'.'
+				}
+				else
+				{
+					'#'
+				}
+			})
+			.collect();
+		let input = format!("{}\n{spurs}\n", ".".repeat(width));
+		let grid = Grid::parse(&input, |x| x);
+		let terminals = [Point::new(0, 0), Point::new(0, width - 1)];
+		let graph = JunctionGraph::from_grid(
+			&grid,
+			&terminals,
+			|&x| x == '.',
+			|_, _| true,
+		);
+		assert_eq!(graph.len(), 71);
+		let path = graph.longest_path(0, graph.len() - 1).unwrap();
+		assert_eq!(path.length, width - 1);
+		assert_eq!(path.junctions, (0..71).collect::<Vec<usize>>());
+	}
+}
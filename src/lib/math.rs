@@ -0,0 +1,37 @@
+/**/
+
+pub fn gcd(mut a: usize, mut b: usize) -> usize
+{
+	if a < b
+	{
+		return gcd(b, a);
+	}
+	while b != 0
+	{
+		let c = a % b;
+		a = b;
+		b = c;
+	}
+	a
+}
+
+pub fn lcm(a: usize, b: usize) -> usize
+{
+	a / gcd(a, b) * b
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_gcd_lcm()
+	{
+		assert_eq!(gcd(12, 18), 6);
+		assert_eq!(gcd(7, 0), 7);
+		assert_eq!(lcm(4, 6), 12);
+		assert_eq!(lcm(1, 3923), 3923);
+	}
+}
@@ -0,0 +1,143 @@
+/**/
+
+/// Bad puzzle input, pointing at where in the input the problem is. Lines
+/// and columns start at 1; columns count characters, not bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError
+{
+	pub line: usize,
+	pub col: usize,
+	pub message: String,
+}
+
+impl ParseError
+{
+	pub fn new(
+		line: usize,
+		col: usize,
+		message: impl Into<String>,
+	) -> ParseError
+	{
+		ParseError {
+			line,
+			col,
+			message: message.into(),
+		}
+	}
+
+	/// An error at the start of `span`, which must be a slice of `input`
+	/// (such as a line or a word of it). Spans from elsewhere are reported
+	/// at the end of the input.
+	pub fn at(input: &str, span: &str, message: impl Into<String>)
+		-> ParseError
+	{
+		let offset = (span.as_ptr() as usize)
+			.checked_sub(input.as_ptr() as usize)
+			.filter(|&x| {
+				x + span.len() <= input.len() && input.is_char_boundary(x)
+			})
+			.unwrap_or(input.len());
+		let before = &input[..offset];
+		let line_start = before.rfind('\n').map_or(0, |x| x + 1);
+		ParseError::new(
+			before.matches('\n').count() + 1,
+			before[line_start..].chars().count() + 1,
+			message,
+		)
+	}
+
+	/// An error at the end of `span`, for when something is missing.
+	pub fn after(
+		input: &str,
+		span: &str,
+		message: impl Into<String>,
+	) -> ParseError
+	{
+		ParseError::at(input, &span[span.len()..], message)
+	}
+}
+
+impl std::fmt::Display for ParseError
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		write!(
+			f,
+			"input line {} col {}: {}",
+			self.line, self.col, self.message
+		)
+	}
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses `word`, a slice of `input`, or reports what was expected there.
+pub fn parse_word<T: std::str::FromStr>(
+	input: &str,
+	word: &str,
+	expected: &str,
+) -> Result<T, ParseError>
+{
+	word.parse().map_err(|_| {
+		ParseError::at(
+			input,
+			word,
+			format!("expected {expected}, found '{word}'"),
+		)
+	})
+}
+
+/// The non-empty lines of `input`.
+pub fn lines(input: &str) -> impl Iterator<Item = &str>
+{
+	input.lines().filter(|line| !line.is_empty())
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_at()
+	{
+		let input = "px{a<2006:qkq}\nin{s<1351:px,m}\n";
+		let line = input.lines().nth(1).unwrap();
+		assert_eq!(
+			ParseError::at(input, &line[2..], "expected '<' or '>'")
+				.to_string(),
+			"input line 2 col 3: expected '<' or '>'"
+		);
+		assert_eq!(
+			ParseError::at(input, input, "x"),
+			ParseError::new(1, 1, "x")
+		);
+		assert_eq!(
+			ParseError::after(input, line, "x"),
+			ParseError::new(2, 16, "x")
+		);
+		assert_eq!(
+			ParseError::at(input, "elsewhere", "x"),
+			ParseError::new(3, 1, "x")
+		);
+		let unicode = "ä.\nöü#";
+		assert_eq!(
+			ParseError::at(unicode, &unicode[8..], "x"),
+			ParseError::new(2, 3, "x")
+		);
+	}
+
+	#[test]
+	fn test_parse_word()
+	{
+		let input = "seeds: 79 x4";
+		assert_eq!(parse_word::<u32>(input, &input[7..9], "a number"), Ok(79));
+		assert_eq!(
+			parse_word::<u32>(input, &input[10..], "a number")
+				.unwrap_err()
+				.to_string(),
+			"input line 1 col 11: expected a number, found 'x4'"
+		);
+	}
+}
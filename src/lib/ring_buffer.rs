@@ -70,6 +70,11 @@ where
 		(self.tail + N - self.head) % N
 	}
 
+	pub fn is_empty(&self) -> bool
+	{
+		self.head == self.tail
+	}
+
 	pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T>
 	{
 		let len = self.len();
@@ -0,0 +1,233 @@
+/**/
+
+use crate::answers::{self, Answers, Verdict};
+use crate::bench::{self, BenchConfig, Nondeterministic};
+use crate::input::{self, InputSource};
+use crate::parse::ParseError;
+use crate::runtime_log::{self, Record};
+use crate::solver::{Part, Solver};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunOptions
+{
+	pub bench: Option<BenchConfig>,
+	/// The day's known-good answers, or `None` if the input is not the day's
+	/// own `input.txt`.
+	pub answers_path: Option<PathBuf>,
+	/// Store the answers as the new known-good ones instead of checking them.
+	pub record: bool,
+}
+
+impl RunOptions
+{
+	/// Benchmarking is controlled by `AOC_BENCH` (see [`BenchConfig`]) and
+	/// recording by `AOC_RECORD`.
+	pub fn from_env(day: &str, source: &InputSource) -> RunOptions
+	{
+		let is_default_input =
+			*source == InputSource::File(input::default_path(day));
+		RunOptions {
+			bench: BenchConfig::from_env(),
+			answers_path: is_default_input.then(|| answers::default_path(day)),
+			record: std::env::var("AOC_RECORD")
+				.is_ok_and(|x| x != "0" && !x.is_empty()),
+		}
+	}
+}
+
+/// How many threads a day may solve with: `AOC_THREADS` if it is set to a
+/// positive number, or else as many as the machine can run at once.
+pub fn num_threads() -> usize
+{
+	std::env::var("AOC_THREADS")
+		.ok()
+		.and_then(|x| x.parse().ok())
+		.filter(|&x| x > 0)
+		.unwrap_or_else(|| {
+			std::thread::available_parallelism().map_or(1, |x| x.get())
+		})
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunError
+{
+	Nondeterministic(Nondeterministic),
+	WrongAnswer
+	{
+		expected: String,
+		actual: String,
+	},
+	Answers(String),
+	Parse(ParseError),
+	/// The input parsed, but the part has no answer for it.
+	Unsolvable(String),
+	Dot(String),
+	Render(String),
+}
+
+impl std::fmt::Display for RunError
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		match self
+		{
+			RunError::Nondeterministic(error) => write!(f, "{error}"),
+			RunError::WrongAnswer { expected, actual } =>
+			{
+				write!(f, "answer {actual} does not match expected {expected}")
+			}
+			RunError::Answers(error) => write!(f, "{error}"),
+			RunError::Parse(error) => write!(f, "{error}"),
+			RunError::Unsolvable(error) => write!(f, "{error}"),
+			RunError::Dot(error) => write!(f, "{error}"),
+			RunError::Render(error) => write!(f, "{error}"),
+		}
+	}
+}
+
+impl std::error::Error for RunError {}
+
+impl From<Nondeterministic> for RunError
+{
+	fn from(error: Nondeterministic) -> RunError
+	{
+		RunError::Nondeterministic(error)
+	}
+}
+
+impl From<ParseError> for RunError
+{
+	fn from(error: ParseError) -> RunError
+	{
+		RunError::Parse(error)
+	}
+}
+
+pub fn run_solver<S: Solver>(
+	day: &str,
+	input: &str,
+	part: Part,
+	options: &RunOptions,
+) -> Result<S::Answer, RunError>
+{
+	// Bad input is reported up front, so that the timed runs cannot fail.
+	S::parse(input)?;
+	run_part(day, part, options, || {
+		let parsed = S::parse(input).expect("input parsed before");
+		S::try_part(&parsed, part).map_err(RunError::Unsolvable)
+	})
+}
+
+/// Writes the day's input as a Graphviz graph to `path`, for days that
+/// implement [`Solver::to_dot`].
+pub fn emit_dot<S: Solver>(input: &str, path: &Path) -> Result<(), RunError>
+{
+	let parsed = S::parse(input)?;
+	let dot = S::to_dot(&parsed)
+		.ok_or_else(|| RunError::Dot("has no graph to export".to_string()))?;
+	dot.write(path)
+		.map_err(|error| RunError::Dot(format!("{}: {error}", path.display())))
+}
+
+/// The picture of how the day solves `part`, for days that implement
+/// [`Solver::render`].
+pub fn render<S: Solver>(input: &str, part: Part) -> Result<String, RunError>
+{
+	let parsed = S::parse(input)?;
+	S::render(&parsed, part)
+		.ok_or_else(|| RunError::Render("has nothing to render".to_string()))
+}
+
+/// Runs `solve` once and prints how long it took, or benchmarks it if asked
+/// to. The answer is then checked against (or recorded as) the known-good
+/// answer. Release builds append the runtime of answers that are not known
+/// to be wrong to the runtime log, unless recording. If `solve` fails,
+/// it does so before anything is benchmarked or recorded.
+pub fn run_part<T: std::fmt::Display + PartialEq>(
+	day: &str,
+	part: Part,
+	options: &RunOptions,
+	mut solve: impl FnMut() -> Result<T, RunError>,
+) -> Result<T, RunError>
+{
+	let (output, runtime_in_ms, timing) = match &options.bench
+	{
+		Some(config) =>
+		{
+			solve()?;
+			let (output, stats) = bench::benchmark(config, || {
+				solve().expect("solved once before")
+			})?;
+			(output, stats.median / 1000.0, stats.to_string())
+		}
+		None =>
+		{
+			let start = std::time::Instant::now();
+			let output = solve()?;
+			let runtime_in_ms = start.elapsed().as_secs_f64() * 1000.0;
+			(output, runtime_in_ms, format!("took {runtime_in_ms:.1} ms"))
+		}
+	};
+	let answer = output.to_string();
+
+	let verdict = match &options.answers_path
+	{
+		Some(path) if options.record =>
+		{
+			record_answer(path, part, &answer)?;
+			Some(Verdict::Recorded)
+		}
+		Some(path) =>
+		{
+			let answers = Answers::read(path).map_err(RunError::Answers)?;
+			Some(answers.check(part, &answer))
+		}
+		None => None,
+	};
+	match &verdict
+	{
+		Some(verdict) => println!(
+			"### {day} part {part}: {output} ### ({timing}) [{verdict}]"
+		),
+		None => println!("### {day} part {part}: {output} ### ({timing})"),
+	}
+
+	let is_logged = cfg!(not(debug_assertions))
+		&& !matches!(verdict, Some(Verdict::Fail { .. } | Verdict::Recorded));
+	if is_logged
+	{
+		log_runtime(day, part, runtime_in_ms, answer.clone());
+	}
+	match verdict
+	{
+		Some(Verdict::Fail { expected }) => Err(RunError::WrongAnswer {
+			expected,
+			actual: answer,
+		}),
+		_ => Ok(output),
+	}
+}
+
+fn record_answer(path: &Path, part: Part, answer: &str)
+	-> Result<(), RunError>
+{
+	let mut answers = Answers::read(path).map_err(RunError::Answers)?;
+	answers.set(part, answer.to_string());
+	answers.write(path).map_err(|error| {
+		RunError::Answers(format!("{}: {error}", path.display()))
+	})
+}
+
+fn log_runtime(day: &str, part: Part, runtime_in_ms: f64, answer: String)
+{
+	let record = Record::new(day, part, runtime_in_ms, answer);
+	let path = runtime_log::default_path();
+	if let Err(error) = runtime_log::append(&path, &record)
+	{
+		eprintln!(
+			"warning: could not log runtime to {}: {error}",
+			path.display()
+		);
+	}
+}
@@ -0,0 +1,462 @@
+/**/
+
+use crate::solver::Part;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const HEADER: &str = "timestamp,git_rev,profile,day,part,runtime_ms,answer";
+
+/// One line of `meta/runtime.log.csv`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record
+{
+	/// Seconds since the Unix epoch.
+	pub timestamp: u64,
+	pub git_rev: String,
+	pub profile: String,
+	pub day: String,
+	pub part: Part,
+	pub runtime_in_ms: f64,
+	pub answer: String,
+}
+
+impl Record
+{
+	pub fn new(
+		day: &str,
+		part: Part,
+		runtime_in_ms: f64,
+		answer: String,
+	) -> Record
+	{
+		let timestamp = std::time::SystemTime::now()
+			.duration_since(std::time::UNIX_EPOCH)
+			.map(|x| x.as_secs())
+			.unwrap_or(0);
+		Record {
+			timestamp,
+			git_rev: current_git_rev().to_string(),
+			profile: current_profile().to_string(),
+			day: day.to_string(),
+			part,
+			runtime_in_ms,
+			answer,
+		}
+	}
+
+	pub fn to_csv(&self) -> String
+	{
+		format!(
+			"{},{},{},{},{},{:.3},{}",
+			self.timestamp,
+			escape(&self.git_rev),
+			escape(&self.profile),
+			escape(&self.day),
+			self.part,
+			self.runtime_in_ms,
+			escape(&self.answer)
+		)
+	}
+
+	pub fn from_csv(line: &str) -> Result<Record, String>
+	{
+		let fields: [String; 7] =
+			split_fields(line)?.try_into().map_err(|x: Vec<String>| {
+				format!("expected 7 fields, got {}", x.len())
+			})?;
+		let [timestamp, git_rev, profile, day, part, runtime, answer] = fields;
+		Ok(Record {
+			timestamp: timestamp
+				.parse()
+				.map_err(|_| format!("invalid timestamp '{timestamp}'"))?,
+			git_rev,
+			profile,
+			day,
+			part: part.parse().map_err(|_| format!("invalid part '{part}'"))?,
+			runtime_in_ms: runtime
+				.parse()
+				.map_err(|_| format!("invalid runtime '{runtime}'"))?,
+			answer,
+		})
+	}
+}
+
+/// The whole log, oldest record first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RuntimeLog
+{
+	pub records: Vec<Record>,
+}
+
+impl RuntimeLog
+{
+	pub fn read(path: &Path) -> Result<RuntimeLog, String>
+	{
+		let contents = std::fs::read_to_string(path)
+			.map_err(|error| format!("{}: {error}", path.display()))?;
+		RuntimeLog::parse(&contents)
+			.map_err(|error| format!("{}: {error}", path.display()))
+	}
+
+	pub fn parse(contents: &str) -> Result<RuntimeLog, String>
+	{
+		let records = contents
+			.lines()
+			.enumerate()
+			.filter(|(_, line)| !line.is_empty() && *line != HEADER)
+			.map(|(i, line)| {
+				Record::from_csv(line)
+					.map_err(|error| format!("line {}: {error}", i + 1))
+			})
+			.collect::<Result<Vec<Record>, String>>()?;
+		Ok(RuntimeLog { records })
+	}
+
+	/// Revisions in the order in which they were first logged.
+	pub fn revisions(&self) -> Vec<&str>
+	{
+		let mut revisions: Vec<&str> = Vec::new();
+		for record in &self.records
+		{
+			if !revisions.contains(&record.git_rev.as_str())
+			{
+				revisions.push(&record.git_rev);
+			}
+		}
+		revisions
+	}
+
+	pub fn for_revision<'a>(
+		&'a self,
+		git_rev: &'a str,
+	) -> impl Iterator<Item = &'a Record> + 'a
+	{
+		self.records.iter().filter(move |x| x.git_rev == git_rev)
+	}
+
+	pub fn history<'a>(
+		&'a self,
+		day: &'a str,
+		part: Part,
+	) -> impl Iterator<Item = &'a Record> + 'a
+	{
+		self.records
+			.iter()
+			.filter(move |x| x.day == day && x.part == part)
+	}
+
+	pub fn latest(&self, day: &str, part: Part) -> Option<&Record>
+	{
+		self.records
+			.iter()
+			.rev()
+			.find(|x| x.day == day && x.part == part)
+	}
+
+	/// Pairs the latest run of every day and part with its baseline, sorted by
+	/// day and part. Only runs from the same build profile are compared; days
+	/// without a baseline are left out.
+	pub fn compare(&self, baseline: &Baseline) -> Vec<Comparison<'_>>
+	{
+		let mut keys: Vec<(&str, Part, &str)> = Vec::new();
+		for record in &self.records
+		{
+			let key =
+				(record.day.as_str(), record.part, record.profile.as_str());
+			if !keys.contains(&key)
+			{
+				keys.push(key);
+			}
+		}
+		keys.sort_by_key(|&(day, part, _)| (day, part as u8));
+
+		let mut comparisons = Vec::new();
+		for (day, part, profile) in keys
+		{
+			let mut runs = self.records.iter().rev().filter(|x| {
+				x.day == day && x.part == part && x.profile == profile
+			});
+			let Some(latest) = runs.next()
+			else
+			{
+				continue;
+			};
+			let found = match baseline
+			{
+				Baseline::Previous => runs.next(),
+				Baseline::Revision(rev) =>
+				{
+					runs.find(|x| x.git_rev.starts_with(rev.as_str()))
+				}
+			};
+			if let Some(baseline) = found
+			{
+				comparisons.push(Comparison { latest, baseline });
+			}
+		}
+		comparisons
+	}
+}
+
+/// What the latest run of each day and part is compared against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Baseline
+{
+	/// The entry logged just before the latest one.
+	Previous,
+	/// The latest entry logged at a git revision starting with this prefix.
+	Revision(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison<'a>
+{
+	pub latest: &'a Record,
+	pub baseline: &'a Record,
+}
+
+impl Comparison<'_>
+{
+	pub fn change_in_percent(&self) -> f64
+	{
+		let baseline = self.baseline.runtime_in_ms;
+		let latest = self.latest.runtime_in_ms;
+		if baseline > 0.0
+		{
+			(latest - baseline) / baseline * 100.0
+		}
+		else if latest > 0.0
+		{
+			f64::INFINITY
+		}
+		else
+		{
+			0.0
+		}
+	}
+
+	pub fn is_regression(&self, threshold_in_percent: f64) -> bool
+	{
+		self.change_in_percent() > threshold_in_percent
+	}
+}
+
+/// `meta/runtime.log.csv` in the crate root.
+pub fn default_path() -> PathBuf
+{
+	Path::new(env!("CARGO_MANIFEST_DIR"))
+		.join("meta")
+		.join("runtime.log.csv")
+}
+
+pub fn append(path: &Path, record: &Record) -> std::io::Result<()>
+{
+	if let Some(dir) = path.parent()
+	{
+		std::fs::create_dir_all(dir)?;
+	}
+	let is_new = !path.exists();
+	let mut log = std::fs::OpenOptions::new()
+		.create(true)
+		.append(true)
+		.open(path)?;
+	if is_new
+	{
+		writeln!(log, "{HEADER}")?;
+	}
+	writeln!(log, "{}", record.to_csv())
+}
+
+pub fn current_profile() -> &'static str
+{
+	if cfg!(debug_assertions)
+	{
+		"debug"
+	}
+	else
+	{
+		"release"
+	}
+}
+
+/// The short hash of `HEAD`, with a `-dirty` suffix if the working tree has
+/// changes, or `unknown` if git is unavailable.
+pub fn current_git_rev() -> &'static str
+{
+	static GIT_REV: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+	GIT_REV.get_or_init(query_git_rev)
+}
+
+fn query_git_rev() -> String
+{
+	let git = |args: &[&str]| {
+		std::process::Command::new("git")
+			.args(args)
+			.current_dir(env!("CARGO_MANIFEST_DIR"))
+			.output()
+			.ok()
+			.filter(|output| output.status.success())
+			.map(|output| {
+				String::from_utf8_lossy(&output.stdout).trim().to_string()
+			})
+	};
+	match git(&["rev-parse", "--short", "HEAD"])
+	{
+		Some(rev) =>
+		{
+			let is_dirty =
+				git(&["status", "--porcelain", "--untracked-files=no"])
+					.is_some_and(|x| !x.is_empty());
+			if is_dirty
+			{
+				format!("{rev}-dirty")
+			}
+			else
+			{
+				rev
+			}
+		}
+		None => "unknown".to_string(),
+	}
+}
+
+fn escape(field: &str) -> String
+{
+	if field.contains([',', '"', '\n'])
+	{
+		format!("\"{}\"", field.replace('"', "\"\""))
+	}
+	else
+	{
+		field.to_string()
+	}
+}
+
+fn split_fields(line: &str) -> Result<Vec<String>, String>
+{
+	let mut fields = Vec::new();
+	let mut field = String::new();
+	let mut is_quoted = false;
+	let mut chars = line.chars().peekable();
+	while let Some(x) = chars.next()
+	{
+		match x
+		{
+			'"' if is_quoted && chars.peek() == Some(&'"') =>
+			{
+				chars.next();
+				field.push('"');
+			}
+			'"' if is_quoted => is_quoted = false,
+			'"' if field.is_empty() => is_quoted = true,
+			',' if !is_quoted => fields.push(std::mem::take(&mut field)),
+			_ => field.push(x),
+		}
+	}
+	if is_quoted
+	{
+		return Err("unterminated quote".to_string());
+	}
+	fields.push(field);
+	Ok(fields)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	fn record(git_rev: &str, day: &str, part: Part, ms: f64) -> Record
+	{
+		Record {
+			timestamp: 1702000000,
+			git_rev: git_rev.to_string(),
+			profile: "release".to_string(),
+			day: day.to_string(),
+			part,
+			runtime_in_ms: ms,
+			answer: "42".to_string(),
+		}
+	}
+
+	#[test]
+	fn test_roundtrip()
+	{
+		let mut a = record("abc1234", "day14", Part::Two, 38.25);
+		a.answer = "a, \"quoted\" answer".to_string();
+		let line = a.to_csv();
+		assert_eq!(
+			line,
+			"1702000000,abc1234,release,day14,two,38.250,\"a, \"\"quoted\"\" \
+			 answer\""
+		);
+		assert_eq!(Record::from_csv(&line), Ok(a));
+	}
+
+	#[test]
+	fn test_log_queries()
+	{
+		let contents = [
+			HEADER.to_string(),
+			record("aaa", "day01", Part::One, 1.0).to_csv(),
+			record("aaa", "day01", Part::Two, 2.0).to_csv(),
+			record("bbb", "day01", Part::One, 0.5).to_csv(),
+			String::new(),
+		]
+		.join("\n");
+		let log = RuntimeLog::parse(&contents).unwrap();
+		assert_eq!(log.records.len(), 3);
+		assert_eq!(log.revisions(), vec!["aaa", "bbb"]);
+		assert_eq!(log.for_revision("aaa").count(), 2);
+		assert_eq!(log.history("day01", Part::One).count(), 2);
+		assert_eq!(log.latest("day01", Part::One).unwrap().runtime_in_ms, 0.5);
+		assert!(RuntimeLog::parse("1,2,3").is_err());
+	}
+
+	#[test]
+	fn test_compare()
+	{
+		let mut debug = record("ccc", "day01", Part::One, 100.0);
+		debug.profile = "debug".to_string();
+		let log = RuntimeLog {
+			records: vec![
+				record("aaa", "day01", Part::One, 1.0),
+				record("aaa", "day02", Part::One, 4.0),
+				record("bbb", "day01", Part::One, 2.0),
+				record("ccc", "day01", Part::One, 2.5),
+				record("ccc", "day02", Part::One, 3.0),
+				debug,
+			],
+		};
+
+		let previous = log.compare(&Baseline::Previous);
+		let changes: Vec<(&str, f64)> = previous
+			.iter()
+			.map(|x| (x.latest.day.as_str(), x.change_in_percent()))
+			.collect();
+		assert_eq!(changes, vec![("day01", 25.0), ("day02", -25.0)]);
+		assert!(previous[0].is_regression(10.0));
+		assert!(!previous[0].is_regression(30.0));
+		assert!(!previous[1].is_regression(10.0));
+
+		let named = log.compare(&Baseline::Revision("aa".to_string()));
+		let changes: Vec<f64> =
+			named.iter().map(|x| x.change_in_percent()).collect();
+		assert_eq!(changes, vec![150.0, -25.0]);
+
+		assert!(log.compare(&Baseline::Revision("zzz".into())).is_empty());
+	}
+
+	#[test]
+	fn test_committed_log()
+	{
+		let log = RuntimeLog::parse(include_str!("../../meta/runtime.log.csv"))
+			.unwrap();
+		for day in 1..=23
+		{
+			let day = format!("day{day:02}");
+			assert!(log.latest(&day, Part::One).is_some(), "{day}");
+		}
+	}
+}
@@ -0,0 +1,206 @@
+/**/
+
+/// Turns `provided_alt.txt` (or just `provided_alt`) into the file name and
+/// the name of the constant holding its contents in the test module.
+pub fn example_names(name: &str) -> Result<(String, String), String>
+{
+	let stem = name.strip_suffix(".txt").unwrap_or(name);
+	let is_valid = !stem.is_empty()
+		&& stem.starts_with(|x: char| x.is_ascii_lowercase())
+		&& stem
+			.chars()
+			.all(|x| x.is_ascii_lowercase() || x.is_ascii_digit() || x == '_');
+	if !is_valid
+	{
+		return Err(format!(
+			"invalid example name '{name}', expected something like \
+			 provided_alt.txt"
+		));
+	}
+	if stem == "provided" || stem == "input"
+	{
+		return Err(format!("'{name}' is always created"));
+	}
+	Ok((format!("{stem}.txt"), stem.to_ascii_uppercase()))
+}
+
+/// Fills the template's test module with a constant and a pair of tests for
+/// every extra example file.
+pub fn render_day(template: &str, examples: &[&str]) -> Result<String, String>
+{
+	const ANCHOR: &str =
+		"\tconst PROVIDED: &str = include_str!(\"provided.txt\");\n";
+	let mut constants = String::new();
+	let mut tests = String::new();
+	for example in examples
+	{
+		let (file_name, constant) = example_names(example)?;
+		let test_name = constant.to_ascii_lowercase();
+		constants.push_str(&format!(
+			"\tconst {constant}: &str = include_str!(\"{file_name}\");\n"
+		));
+		for (part, function) in
+			[("one", "Solution::one"), ("two", "Solution::two")]
+		{
+			tests.push_str(&format!(
+				"\n\t#[test]\n\tfn \
+				 {part}_{test_name}()\n\t{{\n\t\tassert_eq!\
+				 ({function}({constant}), 0);\n\t}}\n"
+			));
+		}
+	}
+
+	let anchor = template
+		.find(ANCHOR)
+		.ok_or("template has no PROVIDED constant")?
+		+ ANCHOR.len();
+	let end_of_tests = template
+		.trim_end()
+		.strip_suffix('}')
+		.ok_or("template does not end with its test module")?
+		.len();
+	Ok([
+		&template[..anchor],
+		&constants,
+		&template[anchor..end_of_tests],
+		&tests,
+		&template[end_of_tests..],
+	]
+	.concat())
+}
+
+/// Adds `dayNN: N => "../dayNN/main.rs",` to the `register_days!` list in
+/// the source of the `aoc` binary, keeping it sorted.
+pub fn register_day(source: &str, number: u8) -> Result<String, String>
+{
+	let start = source
+		.find("register_days! {\n")
+		.ok_or("no register_days! list found")?
+		+ "register_days! {\n".len();
+	let end = start
+		+ source[start..]
+			.find("\n}")
+			.ok_or("unterminated register_days! list")?
+		+ 1;
+	let line = format!(
+		"\tday{number:02}: {number} => \"../day{number:02}/main.rs\",\n"
+	);
+	let mut offset = start;
+	for existing in source[start..end].split_inclusive('\n')
+	{
+		let existing_number = existing
+			.split(':')
+			.next()
+			.and_then(|x| x.trim().strip_prefix("day"))
+			.and_then(|x| x.parse::<u8>().ok());
+		match existing_number
+		{
+			Some(x) if x == number =>
+			{
+				return Err(format!("day{number:02} is already registered"))
+			}
+			Some(x) if x > number => break,
+			_ => offset += existing.len(),
+		}
+	}
+	Ok([&source[..offset], &line, &source[offset..]].concat())
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	const TEMPLATE: &str = "pub fn main() {}
+
+#[cfg(test)]
+mod tests
+{
+	const PROVIDED: &str = include_str!(\"provided.txt\");
+
+	#[test]
+	fn one_provided()
+	{
+		assert_eq!(Solution::one(PROVIDED), 0);
+	}
+}
+";
+
+	#[test]
+	fn test_example_names()
+	{
+		assert_eq!(
+			example_names("provided_alt.txt"),
+			Ok(("provided_alt.txt".to_string(), "PROVIDED_ALT".to_string()))
+		);
+		assert_eq!(
+			example_names("provided_two_tight"),
+			Ok((
+				"provided_two_tight.txt".to_string(),
+				"PROVIDED_TWO_TIGHT".to_string()
+			))
+		);
+		assert!(example_names("provided.txt").is_err());
+		assert!(example_names("../evil.txt").is_err());
+		assert!(example_names("Provided").is_err());
+	}
+
+	#[test]
+	fn test_render_day()
+	{
+		assert_eq!(render_day(TEMPLATE, &[]), Ok(TEMPLATE.to_string()));
+		let rendered = render_day(TEMPLATE, &["provided_alt.txt"]).unwrap();
+		assert_eq!(
+			rendered,
+			"pub fn main() {}
+
+#[cfg(test)]
+mod tests
+{
+	const PROVIDED: &str = include_str!(\"provided.txt\");
+	const PROVIDED_ALT: &str = include_str!(\"provided_alt.txt\");
+
+	#[test]
+	fn one_provided()
+	{
+		assert_eq!(Solution::one(PROVIDED), 0);
+	}
+
+	#[test]
+	fn one_provided_alt()
+	{
+		assert_eq!(Solution::one(PROVIDED_ALT), 0);
+	}
+
+	#[test]
+	fn two_provided_alt()
+	{
+		assert_eq!(Solution::two(PROVIDED_ALT), 0);
+	}
+}
+"
+		);
+	}
+
+	#[test]
+	fn test_register_day()
+	{
+		let source = "register_days! {\n\tday01: 1 => \
+		              \"../day01/main.rs\",\n\tday03: 3 => \
+		              \"../day03/main.rs\",\n}\n";
+		assert_eq!(
+			register_day(source, 2).unwrap(),
+			"register_days! {\n\tday01: 1 => \"../day01/main.rs\",\n\tday02: \
+			 2 => \"../day02/main.rs\",\n\tday03: 3 => \
+			 \"../day03/main.rs\",\n}\n"
+		);
+		assert_eq!(
+			register_day(source, 4).unwrap(),
+			"register_days! {\n\tday01: 1 => \"../day01/main.rs\",\n\tday03: \
+			 3 => \"../day03/main.rs\",\n\tday04: 4 => \
+			 \"../day04/main.rs\",\n}\n"
+		);
+		assert!(register_day(source, 3).is_err());
+	}
+}
@@ -0,0 +1,425 @@
+/**/
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// How the frontier of a search is kept in order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Queue
+{
+	#[default]
+	BinaryHeap,
+	/// One bucket per priority, which is faster when costs are small
+	/// integers. Needs a consistent heuristic (as the ones that never
+	/// overestimate the cost of a single step are), because buckets below
+	/// the one being emptied are never looked at again.
+	Buckets,
+}
+
+/// The cheapest way to a goal, from the start it began at to the goal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found<S>
+{
+	pub cost: u32,
+	pub path: Vec<S>,
+}
+
+impl<S> Found<S>
+{
+	pub fn goal(&self) -> &S
+	{
+		self.path.last().expect("paths include their start")
+	}
+}
+
+/// A shortest path search: Dijkstra's algorithm, or A* once given a
+/// heuristic. `neighbours` gives the states that can be reached from a
+/// state in one step, along with the cost of that step.
+pub struct Search<S, N, H = fn(&S) -> u32, X = fn(&S) -> usize>
+{
+	neighbours: N,
+	heuristic: H,
+	queue: Queue,
+	/// The number of states and a way to number them, if costs are to be
+	/// kept in a `Vec` instead of a `HashMap`.
+	dense: Option<(usize, X)>,
+	_state: PhantomData<fn(&S)>,
+}
+
+impl<S, N, I> Search<S, N>
+where
+	N: FnMut(&S) -> I,
+	I: IntoIterator<Item = (S, u32)>,
+{
+	pub fn new(neighbours: N) -> Self
+	{
+		Search {
+			neighbours,
+			heuristic: |_| 0,
+			queue: Queue::default(),
+			dense: None,
+			_state: PhantomData,
+		}
+	}
+}
+
+impl<S, N, H, X, I> Search<S, N, H, X>
+where
+	S: Clone + Eq + Hash,
+	N: FnMut(&S) -> I,
+	I: IntoIterator<Item = (S, u32)>,
+	H: FnMut(&S) -> u32,
+	X: Fn(&S) -> usize,
+{
+	/// Turns the search into A*. The heuristic must never overestimate the
+	/// remaining cost.
+	pub fn heuristic<H2: FnMut(&S) -> u32>(
+		self,
+		heuristic: H2,
+	) -> Search<S, N, H2, X>
+	{
+		Search {
+			neighbours: self.neighbours,
+			heuristic,
+			queue: self.queue,
+			dense: self.dense,
+			_state: PhantomData,
+		}
+	}
+
+	pub fn queue(self, queue: Queue) -> Self
+	{
+		Search { queue, ..self }
+	}
+
+	/// Keeps the best cost of every state in a `Vec`, which is faster than
+	/// hashing when states can be numbered from 0 to `num_states - 1`.
+	pub fn dense<X2: Fn(&S) -> usize>(
+		self,
+		num_states: usize,
+		index: X2,
+	) -> Search<S, N, H, X2>
+	{
+		Search {
+			neighbours: self.neighbours,
+			heuristic: self.heuristic,
+			queue: self.queue,
+			dense: Some((num_states, index)),
+			_state: PhantomData,
+		}
+	}
+
+	/// The cheapest path from any of `starts` to a state for which
+	/// `is_goal` holds, if there is one.
+	pub fn run(
+		mut self,
+		starts: impl IntoIterator<Item = S>,
+		mut is_goal: impl FnMut(&S) -> bool,
+	) -> Option<Found<S>>
+	{
+		let mut best_costs = match &self.dense
+		{
+			Some((num_states, index)) =>
+			{
+				Costs::Dense(vec![u32::MAX; *num_states], index)
+			}
+			None => Costs::Hashed(HashMap::new()),
+		};
+		let mut nodes: Vec<Node<S>> = Vec::new();
+		let mut frontier = Frontier::new(self.queue);
+		for state in starts
+		{
+			if best_costs.improve(&state, 0)
+			{
+				frontier.push((self.heuristic)(&state), nodes.len());
+				nodes.push(Node {
+					state,
+					cost: 0,
+					parent: None,
+				});
+			}
+		}
+
+		while let Some(i) = frontier.pop()
+		{
+			let cost = nodes[i].cost;
+			if best_costs.get(&nodes[i].state) < cost
+			{
+				continue;
+			}
+			if is_goal(&nodes[i].state)
+			{
+				return Some(Found {
+					cost,
+					path: reconstruct_path(&nodes, i),
+				});
+			}
+			for (next, step_cost) in (self.neighbours)(&nodes[i].state)
+			{
+				let next_cost = cost + step_cost;
+				if best_costs.improve(&next, next_cost)
+				{
+					let priority = next_cost + (self.heuristic)(&next);
+					frontier.push(priority, nodes.len());
+					nodes.push(Node {
+						state: next,
+						cost: next_cost,
+						parent: Some(i),
+					});
+				}
+			}
+		}
+		None
+	}
+}
+
+enum Costs<'a, S, X>
+{
+	Hashed(HashMap<S, u32>),
+	Dense(Vec<u32>, &'a X),
+}
+
+impl<S: Clone + Eq + Hash, X: Fn(&S) -> usize> Costs<'_, S, X>
+{
+	fn get(&self, state: &S) -> u32
+	{
+		match self
+		{
+			Costs::Hashed(costs) =>
+			{
+				costs.get(state).copied().unwrap_or(u32::MAX)
+			}
+			Costs::Dense(costs, index) => costs[index(state)],
+		}
+	}
+
+	/// Records `cost` if it is lower than the best cost so far.
+	fn improve(&mut self, state: &S, cost: u32) -> bool
+	{
+		let best = match self
+		{
+			Costs::Hashed(costs) =>
+			{
+				costs.entry(state.clone()).or_insert(u32::MAX)
+			}
+			Costs::Dense(costs, index) => &mut costs[index(state)],
+		};
+		if cost < *best
+		{
+			*best = cost;
+			true
+		}
+		else
+		{
+			false
+		}
+	}
+}
+
+#[derive(Debug)]
+struct Node<S>
+{
+	state: S,
+	cost: u32,
+	parent: Option<usize>,
+}
+
+fn reconstruct_path<S: Clone>(nodes: &[Node<S>], end: usize) -> Vec<S>
+{
+	let mut path: Vec<S> =
+		std::iter::successors(Some(end), |&i| nodes[i].parent)
+			.map(|i| nodes[i].state.clone())
+			.collect();
+	path.reverse();
+	path
+}
+
+/// Node indices ordered by priority.
+enum Frontier
+{
+	BinaryHeap(BinaryHeap<Reverse<(u32, usize)>>),
+	Buckets
+	{
+		buckets: Vec<Vec<usize>>,
+		current: usize,
+	},
+}
+
+impl Frontier
+{
+	fn new(queue: Queue) -> Frontier
+	{
+		match queue
+		{
+			Queue::BinaryHeap => Frontier::BinaryHeap(BinaryHeap::new()),
+			Queue::Buckets => Frontier::Buckets {
+				buckets: Vec::new(),
+				current: 0,
+			},
+		}
+	}
+
+	fn push(&mut self, priority: u32, i: usize)
+	{
+		match self
+		{
+			Frontier::BinaryHeap(heap) => heap.push(Reverse((priority, i))),
+			Frontier::Buckets { buckets, current } =>
+			{
+				let priority = priority as usize;
+				debug_assert!(priority >= *current, "inconsistent heuristic");
+				let priority = priority.max(*current);
+				if priority >= buckets.len()
+				{
+					buckets.resize_with(priority + 1, Vec::new);
+				}
+				buckets[priority].push(i);
+			}
+		}
+	}
+
+	fn pop(&mut self) -> Option<usize>
+	{
+		match self
+		{
+			Frontier::BinaryHeap(heap) => heap.pop().map(|Reverse((_, i))| i),
+			Frontier::Buckets { buckets, current } =>
+			{
+				while *current < buckets.len()
+				{
+					if let Some(i) = buckets[*current].pop()
+					{
+						return Some(i);
+					}
+					*current += 1;
+				}
+				None
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	type Cell = (usize, usize);
+
+	const MAZE: &str = "..#.\n..#.\n.#..\n....\n";
+
+	fn maze_neighbours(
+		walls: &[Vec<bool>],
+	) -> impl FnMut(&Cell) -> Vec<(Cell, u32)> + '_
+	{
+		|&(r, c)| {
+			let mut next = Vec::new();
+			if r > 0
+			{
+				next.push((r - 1, c));
+			}
+			if c > 0
+			{
+				next.push((r, c - 1));
+			}
+			next.push((r + 1, c));
+			next.push((r, c + 1));
+			next.into_iter()
+				.filter(|&(r, c)| r < walls.len() && c < walls[r].len())
+				.filter(|&(r, c)| !walls[r][c])
+				.map(|x| (x, 1))
+				.collect()
+		}
+	}
+
+	#[test]
+	fn test_dijkstra()
+	{
+		let walls: Vec<Vec<bool>> = MAZE
+			.lines()
+			.map(|line| line.chars().map(|x| x == '#').collect())
+			.collect();
+		let goal = (3, 3);
+		for queue in [Queue::BinaryHeap, Queue::Buckets]
+		{
+			let found = Search::new(maze_neighbours(&walls))
+				.queue(queue)
+				.run([(0, 0)], |&x| x == goal)
+				.unwrap();
+			assert_eq!(found.cost, 6);
+			assert_eq!(found.path.len(), 7);
+			assert_eq!(found.path[0], (0, 0));
+			assert_eq!(found.goal(), &goal);
+			for pair in found.path.windows(2)
+			{
+				let ((r0, c0), (r1, c1)) = (pair[0], pair[1]);
+				assert_eq!(r0.abs_diff(r1) + c0.abs_diff(c1), 1);
+			}
+		}
+		assert_eq!(
+			Search::new(maze_neighbours(&walls))
+				.run([(0, 0)], |&x| x == (0, 3))
+				.map(|found| found.cost),
+			Some(9)
+		);
+		assert_eq!(
+			Search::new(maze_neighbours(&walls)).run([(0, 0)], |_| false),
+			None
+		);
+	}
+
+	#[test]
+	fn test_a_star()
+	{
+		let walls = vec![vec![false; 8]; 8];
+		let goal = (7, 7);
+		let heuristics: [fn(&Cell) -> u32; 2] =
+			[|_| 0, |&(r, c)| (7 - r + 7 - c) as u32];
+		let mut num_expanded = [0; 2];
+		for (k, heuristic) in heuristics.into_iter().enumerate()
+		{
+			for queue in [Queue::BinaryHeap, Queue::Buckets]
+			{
+				let mut neighbours = maze_neighbours(&walls);
+				let found = Search::new(|x: &Cell| {
+					num_expanded[k] += 1;
+					neighbours(x)
+				})
+				.heuristic(heuristic)
+				.queue(queue)
+				.dense(64, |&(r, c)| r * 8 + c)
+				.run([(0, 0)], |&x| x == goal)
+				.unwrap();
+				assert_eq!(found.cost, 14);
+			}
+		}
+		assert!(num_expanded[1] < num_expanded[0]);
+	}
+
+	#[test]
+	fn test_weighted()
+	{
+		// Going around the expensive middle node is cheaper.
+		let edges: &[(u8, u8, u32)] =
+			&[(0, 1, 1), (1, 3, 10), (0, 2, 2), (2, 4, 2), (4, 3, 3)];
+		let neighbours = |&x: &u8| {
+			edges
+				.iter()
+				.filter(move |&&(from, _, _)| from == x)
+				.map(|&(_, to, cost)| (to, cost))
+		};
+		for queue in [Queue::BinaryHeap, Queue::Buckets]
+		{
+			assert_eq!(
+				Search::new(neighbours).queue(queue).run([0], |&x| x == 3),
+				Some(Found {
+					cost: 7,
+					path: vec![0, 2, 4, 3]
+				})
+			);
+		}
+	}
+}
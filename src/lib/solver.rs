@@ -0,0 +1,80 @@
+/**/
+
+use crate::dot::Dot;
+use crate::parse::ParseError;
+use parse_display::{Display, FromStr};
+
+pub trait Solver
+{
+	type Parsed<'a>;
+	type Answer: std::fmt::Display + PartialEq;
+
+	fn parse(input: &str) -> Result<Self::Parsed<'_>, ParseError>;
+	fn part_one(parsed: &Self::Parsed<'_>) -> Self::Answer;
+	fn part_two(parsed: &Self::Parsed<'_>) -> Self::Answer;
+
+	/// Solves a part, or says why there is no answer for input that parsed
+	/// fine, such as input that only one of the parts can make sense of.
+	/// Days whose parts can fail override this, and have `part_one` and
+	/// `part_two` panic with the reason.
+	fn try_part(
+		parsed: &Self::Parsed<'_>,
+		part: Part,
+	) -> Result<Self::Answer, String>
+	{
+		Ok(match part
+		{
+			Part::One => Self::part_one(parsed),
+			Part::Two => Self::part_two(parsed),
+		})
+	}
+
+	/// A Graphviz rendering of the parsed input, for days whose input is a
+	/// graph worth looking at.
+	fn to_dot(_parsed: &Self::Parsed<'_>) -> Option<Dot>
+	{
+		None
+	}
+
+	/// A picture of how a part was solved, for days where that is worth
+	/// looking at.
+	fn render(_parsed: &Self::Parsed<'_>, _part: Part) -> Option<String>
+	{
+		None
+	}
+
+	/// Solves part one, panicking if the input does not parse.
+	fn one(input: &str) -> Self::Answer
+	{
+		Self::solve(input, Part::One).unwrap_or_else(|error| panic!("{error}"))
+	}
+
+	/// Solves part two, panicking if the input does not parse.
+	fn two(input: &str) -> Self::Answer
+	{
+		Self::solve(input, Part::Two).unwrap_or_else(|error| panic!("{error}"))
+	}
+
+	fn solve(input: &str, part: Part) -> Result<Self::Answer, ParseError>
+	{
+		let parsed = Self::parse(input)?;
+		Ok(match part
+		{
+			Part::One => Self::part_one(&parsed),
+			Part::Two => Self::part_two(&parsed),
+		})
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, FromStr)]
+#[display(style = "lowercase")]
+pub enum Part
+{
+	One,
+	Two,
+}
+
+impl Part
+{
+	pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+}
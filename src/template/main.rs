@@ -1,23 +1,36 @@
 //!
 
-use aoc2023::run;
-
-const INPUT: &str = include_str!("input.txt");
+use aoc2023::parse::ParseError;
+use aoc2023::solver::Solver;
+use aoc2023::{load_input, run};
 
 pub fn main()
 {
-	run!(one(INPUT));
-	run!(two(INPUT));
+	let input = load_input!();
+	run!(Solution, &input);
 }
 
-fn one(input: &str) -> usize
-{
-	input.len() * 0
-}
+pub struct Solution;
 
-fn two(input: &str) -> usize
+impl Solver for Solution
 {
-	input.len() * 0
+	type Parsed<'a> = &'a str;
+	type Answer = usize;
+
+	fn parse(input: &str) -> Result<&str, ParseError>
+	{
+		Ok(input)
+	}
+
+	fn part_one(input: &&str) -> usize
+	{
+		input.len() * 0
+	}
+
+	fn part_two(input: &&str) -> usize
+	{
+		input.len() * 0
+	}
 }
 
 #[cfg(test)]
@@ -31,12 +44,12 @@ mod tests
 	#[test]
 	fn one_provided()
 	{
-		assert_eq!(one(PROVIDED), 0);
+		assert_eq!(Solution::one(PROVIDED), 0);
 	}
 
 	#[test]
 	fn two_provided()
 	{
-		assert_eq!(two(PROVIDED), 0);
+		assert_eq!(Solution::two(PROVIDED), 0);
 	}
 }